@@ -0,0 +1,372 @@
+//! The `apinotes` command-line tool.
+//!
+//! Run `apinotes help` for usage.
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+use apinotes::{ApiNotes, ConflictPolicy, Error, ErrorKind, Version};
+use serde_json::{json, Value};
+
+const USAGE: &str = "\
+Usage: apinotes <COMMAND> [OPTIONS] [ARGS]
+
+Commands:
+  check <FILE>...           Parse and validate the files
+  dump <FILE>               Print the parsed notes
+  fmt <FILE>...             Format the files in the canonical layout
+  diff <OLD> <NEW>          Print the entries and attributes that changed
+  query <FILE> <PATH>       Print the entries at PATH, e.g. `Classes[A].Methods[-init]`
+                            or `Classes[*].Properties[*]`
+  merge <FILE>...           Merge the files into one
+  help                      Print this message
+
+Options:
+  --json                    Print output as JSON (check, dump, diff, query, merge)
+  --swift-version <VERSION> Resolve the notes for a Swift version (dump, query)
+  --check                   Only check whether the files are formatted (fmt)
+  --write                   Write the formatted files in place (fmt)
+  -o, --output <FILE>       Write the merged notes to FILE (merge)
+  --prefer <left|right>     Resolve conflicts with the earlier or later file (merge)
+
+Exit status:
+  0  Success
+  1  Problems were found, e.g. the files are invalid, unformatted or differ
+  2  The tool could not run, e.g. due to invalid arguments or unreadable files
+";
+
+/// A failure that prevents a command from running.
+type Failure = String;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        eprint!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let result = match command.as_str() {
+        "check" => check(args),
+        "dump" => dump(args),
+        "fmt" => fmt(args),
+        "diff" => diff(args),
+        "query" => query(args),
+        "merge" => merge(args),
+        "help" | "-h" | "--help" => {
+            print!("{USAGE}");
+            Ok(true)
+        }
+        _ => Err(format!("unknown command `{command}`, see `apinotes help`")),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(failure) => {
+            eprintln!("error: {failure}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// The parsed arguments of a command.
+struct Args {
+    positional: Vec<String>,
+    flags: HashSet<&'static str>,
+    values: HashMap<&'static str, String>,
+}
+
+impl Args {
+    /// Parse `args`, allowing the given flags and options that take a value.
+    fn parse(
+        args: &[String],
+        flags: &[&'static str],
+        options: &[&'static str],
+    ) -> Result<Self, Failure> {
+        let mut parsed = Self {
+            positional: Vec::new(),
+            flags: HashSet::new(),
+            values: HashMap::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = if arg == "-o" { "--output" } else { arg };
+            if let Some(flag) = flags.iter().find(|flag| **flag == name) {
+                parsed.flags.insert(flag);
+            } else if let Some(option) = options.iter().find(|option| **option == name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("`{arg}` requires a value"))?;
+                parsed.values.insert(option, value.clone());
+            } else if arg.starts_with('-') && arg != "-" {
+                return Err(format!("unexpected option `{arg}`"));
+            } else {
+                parsed.positional.push(arg.clone());
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn json(&self) -> bool {
+        self.flags.contains("--json")
+    }
+
+    fn swift_version(&self) -> Result<Option<Version>, Failure> {
+        self.values
+            .get("--swift-version")
+            .map(|version| version.parse().map_err(|err: Error| err.to_string()))
+            .transpose()
+    }
+
+    fn files(&self, min: usize, max: Option<usize>) -> Result<&[String], Failure> {
+        let n = self.positional.len();
+        if n < min || max.is_some_and(|max| n > max) {
+            return Err(format!(
+                "unexpected number of arguments ({n}), see `apinotes help`"
+            ));
+        }
+        Ok(&self.positional)
+    }
+}
+
+/// Read the notes in `path`, or print why they are invalid and return `None`.
+fn read(path: &str) -> Result<Option<ApiNotes>, Failure> {
+    valid(ApiNotes::from_path(Path::new(path)), "")
+}
+
+/// The parsed value, or `None` after printing an error with the contents of
+/// a file, e.g. a syntax error.
+///
+/// Fails if the file couldn't be read at all.
+fn valid<T>(result: Result<T, Error>, prefix: &str) -> Result<Option<T>, Failure> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        // An unreadable file isn't a problem with the file itself.
+        Err(err) if matches!(err.kind(), ErrorKind::Io(_)) => Err(format!("{prefix}{err}")),
+        Err(err) => {
+            eprintln!("{prefix}{err}");
+            Ok(None)
+        }
+    }
+}
+
+fn to_yaml(notes: &ApiNotes) -> Result<String, Failure> {
+    notes.to_canonical_string().map_err(|err| err.to_string())
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<(), Failure> {
+    let json = serde_json::to_string_pretty(value).map_err(|err| err.to_string())?;
+    println!("{json}");
+    Ok(())
+}
+
+/// The notes that apply for the given Swift version, without any
+/// `SwiftVersions` section.
+fn resolve(notes: &ApiNotes, version: Option<Version>) -> ApiNotes {
+    match version {
+        Some(version) => {
+            let mut resolved = ApiNotes::default();
+            resolved.name = notes.name.clone();
+            resolved.data = notes.for_swift_version(version);
+            resolved
+        }
+        None => notes.clone(),
+    }
+}
+
+fn check(args: &[String]) -> Result<bool, Failure> {
+    let args = Args::parse(args, &["--json"], &[])?;
+    let files = args.files(1, None)?;
+
+    let mut ok = true;
+    let mut report = Vec::new();
+    for file in files {
+        let mut problems = Vec::new();
+        match ApiNotes::from_path(Path::new(file)) {
+            Ok(notes) => {
+                for diagnostic in notes.validate() {
+                    if !args.json() {
+                        eprintln!("{file}: {diagnostic}");
+                    }
+                    problems.push(json!({
+                        "path": diagnostic.path,
+                        "message": diagnostic.to_string(),
+                    }));
+                }
+            }
+            // An unreadable file isn't a problem with the file itself.
+            Err(err) if matches!(err.kind(), ErrorKind::Io(_)) => return Err(err.to_string()),
+            Err(err) => {
+                if !args.json() {
+                    eprintln!("{err}");
+                }
+                problems.push(json!({
+                    "kind": format!("{:?}", err.kind()),
+                    "message": err.to_string(),
+                    "line": err.mark().map(|mark| mark.line),
+                    "column": err.mark().map(|mark| mark.column),
+                }));
+            }
+        }
+        ok &= problems.is_empty();
+        report.push(json!({ "file": file, "problems": problems }));
+    }
+
+    if args.json() {
+        print_json(&report)?;
+    }
+    Ok(ok)
+}
+
+fn dump(args: &[String]) -> Result<bool, Failure> {
+    let args = Args::parse(args, &["--json"], &["--swift-version"])?;
+    let [file] = args.files(1, Some(1))? else {
+        unreachable!()
+    };
+    let Some(notes) = read(file)? else {
+        return Ok(false);
+    };
+    let notes = resolve(&notes, args.swift_version()?);
+    if args.json() {
+        print_json(&notes)?;
+    } else {
+        print!("{}", to_yaml(&notes)?);
+    }
+    Ok(true)
+}
+
+fn fmt(args: &[String]) -> Result<bool, Failure> {
+    let args = Args::parse(args, &["--check", "--write"], &[])?;
+    let files = args.files(1, None)?;
+    let check = args.flags.contains("--check");
+    let write = args.flags.contains("--write");
+    if !check && !write && files.len() > 1 {
+        return Err("formatting multiple files requires `--check` or `--write`".into());
+    }
+
+    let mut ok = true;
+    for file in files {
+        let source = fs::read(file).map_err(|err| format!("{file}: {err}"))?;
+        let Some(notes) = valid(ApiNotes::from_slice(&source), &format!("{file}: "))? else {
+            ok = false;
+            continue;
+        };
+        let formatted = to_yaml(&notes)?;
+        if check {
+            if formatted.as_bytes() != source {
+                println!("{file}");
+                ok = false;
+            }
+        } else if write {
+            if formatted.as_bytes() != source {
+                fs::write(file, formatted).map_err(|err| format!("{file}: {err}"))?;
+            }
+        } else {
+            print!("{formatted}");
+        }
+    }
+    Ok(ok)
+}
+
+fn diff(args: &[String]) -> Result<bool, Failure> {
+    let args = Args::parse(args, &["--json"], &[])?;
+    let [old, new] = args.files(2, Some(2))? else {
+        unreachable!()
+    };
+    let (Some(old), Some(new)) = (read(old)?, read(new)?) else {
+        return Ok(false);
+    };
+    let changes = old.diff(&new);
+    if args.json() {
+        print_json(&changes)?;
+    } else {
+        for change in &changes {
+            println!("{change}");
+        }
+    }
+    Ok(changes.is_empty())
+}
+
+fn query(args: &[String]) -> Result<bool, Failure> {
+    let args = Args::parse(args, &["--json"], &["--swift-version"])?;
+    let [file, path] = args.files(2, Some(2))? else {
+        unreachable!()
+    };
+    let Some(notes) = read(file)? else {
+        return Ok(false);
+    };
+    let notes = resolve(&notes, args.swift_version()?);
+    let selections = notes.select(path).map_err(|err| err.to_string())?;
+
+    match selections.as_slice() {
+        [] => {
+            eprintln!("{file}: no entry at `{path}`");
+            return Ok(false);
+        }
+        // Print a single match on its own, unless it was found with a
+        // wildcard, so that scripts get the same output for any notes.
+        [selection] if !path.contains('*') => {
+            let value = &selection.value;
+            if args.json() || value.is_mapping() || value.is_sequence() {
+                print_json(value)?;
+            } else {
+                println!("{}", display(value)?);
+            }
+        }
+        selections => {
+            if args.json() {
+                let selections: Vec<_> = selections
+                    .iter()
+                    .map(|selection| json!({ "path": selection.path, "value": selection.value }))
+                    .collect();
+                print_json(&selections)?;
+            } else {
+                for selection in selections {
+                    println!("{}: {}", selection.path, display(&selection.value)?);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Scalars as plain text, and anything else as compact JSON.
+fn display(value: &impl serde::Serialize) -> Result<String, Failure> {
+    Ok(
+        match serde_json::to_value(value).map_err(|err| err.to_string())? {
+            Value::String(s) => s,
+            value => value.to_string(),
+        },
+    )
+}
+
+fn merge(args: &[String]) -> Result<bool, Failure> {
+    let args = Args::parse(args, &["--json"], &["--output", "--prefer"])?;
+    let files = args.files(1, None)?;
+    let policy = match args.values.get("--prefer").map(String::as_str) {
+        None => ConflictPolicy::Error,
+        Some("left") => ConflictPolicy::PreferLeft,
+        Some("right") => ConflictPolicy::PreferRight,
+        Some(other) => return Err(format!("unknown `--prefer` value `{other}`")),
+    };
+
+    let Some((merged, report)) = valid(ApiNotes::merge_files(files, policy), "")? else {
+        return Ok(false);
+    };
+    for conflict in &report.conflicts {
+        eprintln!("{conflict}");
+    }
+    if report.unresolved {
+        return Ok(false);
+    }
+
+    let output = if args.json() {
+        serde_json::to_string_pretty(&merged).map_err(|err| err.to_string())? + "\n"
+    } else {
+        to_yaml(&merged)?
+    };
+    match args.values.get("--output") {
+        Some(path) => fs::write(path, output).map_err(|err| format!("{path}: {err}"))?,
+        None => print!("{output}"),
+    }
+    Ok(true)
+}
@@ -0,0 +1,191 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn fixture(name: &str) -> String {
+    format!(
+        "{}/../src/clang_tests/{name}.apinotes",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
+
+fn temp_file(name: &str, contents: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_apinotes"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn check() {
+    let output = run(&["check", &fixture("SomeKit"), &fixture("SimpleKit")]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let invalid = temp_file(
+        "check_invalid.apinotes",
+        "Name: A\nClasses:\n  - Name: B\n    Availability: sometimes\n",
+    );
+    let output = run(&["check", "--json", &fixture("SomeKit"), &invalid]);
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report[0]["problems"], serde_json::json!([]));
+    assert_eq!(report[1]["problems"][0]["kind"], "UnknownVariant");
+    assert_eq!(report[1]["problems"][0]["line"], 4);
+
+    let output = run(&["check", "does-not-exist.apinotes"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = run(&[
+        "check",
+        "--json",
+        &fixture("SomeKit"),
+        "does-not-exist.apinotes",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(run(&[]).status.code(), Some(2));
+    assert_eq!(run(&["frobnicate"]).status.code(), Some(2));
+    assert_eq!(run(&["dump"]).status.code(), Some(2));
+    assert_eq!(
+        run(&["dump", "--unknown", &fixture("SomeKit")])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(run(&["help"]).status.code(), Some(0));
+}
+
+#[test]
+fn dump_and_query() {
+    let output = run(&["dump", "--json", &fixture("SomeKit")]);
+    assert_eq!(output.status.code(), Some(0));
+    let notes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notes["Name"], "SomeKit");
+
+    let output = run(&[
+        "query",
+        &fixture("SomeKit"),
+        "Classes[A].Methods[-transform:].Availability",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "none\n");
+
+    let output = run(&[
+        "query",
+        &fixture("SomeKit"),
+        "Classes[A].Methods[+transform:]",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run(&[
+        "query",
+        &fixture("SomeKit"),
+        "Classes[*].Methods[transform:integer:].NullabilityOfRet",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "Classes[A].Methods[-transform:integer:].NullabilityOfRet: Nonnull\n"
+    );
+
+    let output = run(&[
+        "query",
+        "--json",
+        "--swift-version",
+        "3",
+        &fixture("SomeKit"),
+        "Classes[A].Methods[*].NullabilityOfRet",
+    ]);
+    let selections: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        selections,
+        serde_json::json!([{
+            "path": "Classes[A].Methods[-transform:integer:].NullabilityOfRet",
+            "value": "Optional",
+        }])
+    );
+
+    let output = run(&["query", &fixture("SomeKit"), "Classes[A"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn fmt_diff_merge() {
+    let a = temp_file("merge_a.apinotes", "Name: A\nFunctions:\n- Name: f\n");
+    let b = temp_file(
+        "merge_b.apinotes",
+        "Name: A\nFunctions:\n  - Name: g\n    SwiftPrivate: true\n    SwiftName: g()\n",
+    );
+    let conflicting = temp_file(
+        "merge_conflicting.apinotes",
+        "Name: A\nFunctions:\n  - Name: g\n    SwiftName: h()\n",
+    );
+
+    let output = run(&["fmt", "--check", &a]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), format!("{a}\n"));
+    assert_eq!(run(&["fmt", "--write", &a]).status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(&a).unwrap(),
+        "Name: A\nFunctions:\n  - Name: f\n"
+    );
+    assert_eq!(run(&["fmt", "--check", &a]).status.code(), Some(0));
+
+    assert_eq!(run(&["diff", &a, &a]).status.code(), Some(0));
+    let output = run(&["diff", &a, &b]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "- Functions[f]\n+ Functions[g]\n");
+
+    let output = run(&["merge", &a, &b]);
+    assert_eq!(output.status.code(), Some(0));
+    let merged = temp_file("merged.apinotes", &stdout(&output));
+    let output = run(&["query", &merged, "Functions[g].SwiftPrivate"]);
+    assert_eq!(stdout(&output), "true\n");
+
+    let output = run(&["merge", &b, &conflicting]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!("Functions[g].SwiftName: conflicting values `g()` ({b}:5:5) and `h()` ({conflicting}:4:5)\n")
+    );
+
+    let output = run(&["merge", "--prefer", "right", &b, &conflicting]);
+    assert_eq!(output.status.code(), Some(0));
+    let merged = temp_file("merged_right.apinotes", &stdout(&output));
+    let output = run(&["query", &merged, "Functions[g].SwiftName"]);
+    assert_eq!(stdout(&output), "h()\n");
+}
+
+#[test]
+fn invalid_files() {
+    let invalid = temp_file("invalid.apinotes", "Name: A\nClasses: 3\n");
+    let valid = fixture("SimpleKit");
+    for args in [
+        &["dump", &invalid][..],
+        &["fmt", &invalid],
+        &["fmt", "--check", &valid, &invalid],
+        &["diff", &valid, &invalid],
+        &["query", &invalid, "Classes"],
+        &["merge", &valid, &invalid],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(&invalid), "{args:?}: {stderr}");
+    }
+
+    let output = run(&["diff", &valid, "does-not-exist.apinotes"]);
+    assert_eq!(output.status.code(), Some(2));
+}
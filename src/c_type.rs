@@ -0,0 +1,650 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, ErrorKind};
+
+/// A parsed C or Objective-C type, as written in a `Type` or `ResultType`
+/// attribute.
+///
+/// The attributes themselves are kept as text, see [`Type`][crate::Type],
+/// so that notes with types this parser doesn't understand can still be
+/// read. Parse them with [`str::parse`] when needed.
+///
+/// ```
+/// use apinotes::{CType, CTypeKind, TypeNullability};
+///
+/// let ty: CType = "NSArray<NSString *> * _Nullable".parse().unwrap();
+/// assert_eq!(ty.nullability, Some(TypeNullability::Nullable));
+/// let CTypeKind::Pointer(pointee) = &ty.kind else { panic!() };
+/// let CTypeKind::Named { name, arguments } = &pointee.kind else { panic!() };
+/// assert_eq!(name, "NSArray");
+/// assert_eq!(arguments[0].to_string(), "NSString *");
+///
+/// let ty: CType = "void (^)(int x, ...)".parse().unwrap();
+/// assert_eq!(ty.to_string(), "void (^)(int, ...)");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CType {
+    /// What kind of type this is.
+    pub kind: CTypeKind,
+    /// The qualifiers of the type, e.g. `const`.
+    pub qualifiers: Qualifiers,
+    /// The nullability qualifier of the type, e.g. `_Nonnull`.
+    pub nullability: Option<TypeNullability>,
+}
+
+/// The kind of a [`CType`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CTypeKind {
+    /// A builtin type, typedef, tag type or Objective-C class, e.g.
+    /// `unsigned int`, `struct foo`, `NSArray<NSString *>` or
+    /// `id<NSCopying>`.
+    Named {
+        /// The name, including `struct`, `union` or `enum` for tag types.
+        ///
+        /// The words of builtin types like `unsigned long` are separated by
+        /// single spaces, in the order they were written.
+        name: String,
+        /// The type arguments of an Objective-C generic class, or the
+        /// protocols of a qualified `id` or class type.
+        arguments: Vec<CType>,
+    },
+    /// A pointer to a type, e.g. `int *`.
+    Pointer(Box<CType>),
+    /// A block pointer, e.g. `void (^)(int)`.
+    Block(FunctionType),
+    /// A function type, usually behind a [`CTypeKind::Pointer`], e.g.
+    /// `void (*)(int)`.
+    Function(FunctionType),
+    /// An array type, e.g. `int [4]`.
+    Array {
+        /// The type of the elements.
+        element: Box<CType>,
+        /// The number of elements, if specified.
+        size: Option<u64>,
+    },
+}
+
+/// The signature of a [`CTypeKind::Block`] or [`CTypeKind::Function`].
+///
+/// Parameter names are not kept.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    /// The return type.
+    pub result: Box<CType>,
+    /// The types of the parameters, empty for `(void)`.
+    pub parameters: Vec<CType>,
+    /// Whether the function takes variadic arguments, i.e. ends in `...`.
+    pub variadic: bool,
+}
+
+/// The qualifiers of a [`CType`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Qualifiers {
+    /// `const`
+    pub is_const: bool,
+    /// `volatile`
+    pub is_volatile: bool,
+    /// `restrict`
+    pub is_restrict: bool,
+    /// `__kindof`, which allows subclasses of an Objective-C class.
+    pub is_kindof: bool,
+    /// The ARC ownership qualifier, e.g. `__weak`.
+    pub ownership: Option<Ownership>,
+}
+
+/// An Objective-C ARC ownership qualifier on a [`CType`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ownership {
+    /// `__strong`
+    Strong,
+    /// `__weak`
+    Weak,
+    /// `__autoreleasing`
+    Autoreleasing,
+    /// `__unsafe_unretained`
+    UnsafeUnretained,
+}
+
+/// A nullability qualifier on a [`CType`].
+///
+/// The older spellings `__nonnull`, `__nullable` and `__null_unspecified`
+/// are accepted when parsing, but printed like the current ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TypeNullability {
+    /// `_Nonnull`
+    Nonnull,
+    /// `_Nullable`
+    Nullable,
+    /// `_Nullable_result`
+    NullableResult,
+    /// `_Null_unspecified`
+    Unspecified,
+}
+
+impl TypeNullability {
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "_Nonnull" | "__nonnull" => Some(Self::Nonnull),
+            "_Nullable" | "__nullable" => Some(Self::Nullable),
+            "_Nullable_result" => Some(Self::NullableResult),
+            "_Null_unspecified" | "__null_unspecified" => Some(Self::Unspecified),
+            _ => None,
+        }
+    }
+
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::Nonnull => "_Nonnull",
+            Self::Nullable => "_Nullable",
+            Self::NullableResult => "_Nullable_result",
+            Self::Unspecified => "_Null_unspecified",
+        }
+    }
+}
+
+impl CType {
+    fn new(kind: CTypeKind) -> Self {
+        Self {
+            kind,
+            qualifiers: Qualifiers::default(),
+            nullability: None,
+        }
+    }
+
+    /// A named type without qualifiers, e.g. `int` or `NSString`.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::new(CTypeKind::Named {
+            name: name.into(),
+            arguments: Vec::new(),
+        })
+    }
+
+    /// A pointer to `pointee`, without qualifiers.
+    pub fn pointer(pointee: CType) -> Self {
+        Self::new(CTypeKind::Pointer(Box::new(pointee)))
+    }
+}
+
+impl Qualifiers {
+    /// Set the qualifier named by `keyword`, returning `false` if it isn't
+    /// a qualifier.
+    fn add(&mut self, keyword: &str) -> bool {
+        match keyword {
+            "const" => self.is_const = true,
+            "volatile" => self.is_volatile = true,
+            "restrict" | "__restrict" => self.is_restrict = true,
+            "__kindof" => self.is_kindof = true,
+            "__strong" => self.ownership = Some(Ownership::Strong),
+            "__weak" => self.ownership = Some(Ownership::Weak),
+            "__autoreleasing" => self.ownership = Some(Ownership::Autoreleasing),
+            "__unsafe_unretained" => self.ownership = Some(Ownership::UnsafeUnretained),
+            _ => return false,
+        }
+        true
+    }
+
+    fn keywords(self) -> impl Iterator<Item = &'static str> {
+        [
+            (self.is_kindof, "__kindof"),
+            (self.is_const, "const"),
+            (self.is_volatile, "volatile"),
+            (self.is_restrict, "restrict"),
+        ]
+        .into_iter()
+        .filter_map(|(is_set, keyword)| is_set.then_some(keyword))
+        .chain(self.ownership.map(Ownership::keyword))
+    }
+}
+
+impl Ownership {
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::Strong => "__strong",
+            Self::Weak => "__weak",
+            Self::Autoreleasing => "__autoreleasing",
+            Self::UnsafeUnretained => "__unsafe_unretained",
+        }
+    }
+}
+
+/// How deeply types may be nested, to bound the recursion when parsing and
+/// printing them.
+const MAX_DEPTH: usize = 64;
+
+const BUILTIN_WORDS: &[&str] = &[
+    "void", "char", "short", "int", "long", "float", "double", "signed", "unsigned", "_Bool",
+    "bool", "_Complex", "__int128",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Word(&'a str),
+    Number(u64),
+    Punct(&'static str),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token<'_>>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_alphabetic() || c == '_' || c == '$' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Word(&rest[..len]));
+            len
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let size = rest[..len].parse().map_err(|_| "array size is too large")?;
+            tokens.push(Token::Number(size));
+            len
+        } else if rest.starts_with("...") {
+            tokens.push(Token::Punct("..."));
+            3
+        } else {
+            let punct = ["*", "^", "(", ")", "[", "]", "<", ">", ","]
+                .into_iter()
+                .find(|punct| rest.starts_with(punct))
+                .ok_or("unexpected character")?;
+            tokens.push(Token::Punct(punct));
+            1
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    /// How deeply the type being parsed is nested so far.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if p == punct)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let found = self.peek_punct(punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_punct(&mut self, punct: &str, err: &'static str) -> Result<(), &'static str> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Account for another level of nesting.
+    fn deepen(&mut self) -> Result<(), &'static str> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("type is nested too deeply");
+        }
+        Ok(())
+    }
+
+    /// A full type, optionally followed by a name if `allow_name` is set.
+    fn parse_type(&mut self, allow_name: bool) -> Result<CType, &'static str> {
+        let depth = self.depth;
+        self.deepen()?;
+        let base = self.parse_specifiers()?;
+        let ty = self.parse_declarator(base, allow_name)?;
+        self.depth = depth;
+        Ok(ty)
+    }
+
+    /// The qualifiers and type name before any pointers.
+    fn parse_specifiers(&mut self) -> Result<CType, &'static str> {
+        let mut qualifiers = Qualifiers::default();
+        let mut nullability = None;
+        let mut words: Vec<&str> = Vec::new();
+        let mut is_builtin = false;
+        let mut arguments = Vec::new();
+        while let Some(Token::Word(word)) = self.peek() {
+            if word == "_Atomic" {
+                return Err("`_Atomic` types are not supported");
+            } else if qualifiers.add(word) {
+            } else if let Some(found) = TypeNullability::from_keyword(word) {
+                nullability = Some(found);
+            } else if words.is_empty() && matches!(word, "struct" | "union" | "enum") {
+                self.pos += 1;
+                let Some(Token::Word(tag)) = self.peek() else {
+                    return Err("expected a name after `struct`, `union` or `enum`");
+                };
+                words.extend([word, tag]);
+            } else if BUILTIN_WORDS.contains(&word) && (words.is_empty() || is_builtin) {
+                words.push(word);
+                is_builtin = true;
+            } else if words.is_empty() {
+                words.push(word);
+                self.pos += 1;
+                if self.eat_punct("<") {
+                    loop {
+                        arguments.push(self.parse_type(false)?);
+                        if self.eat_punct(">") {
+                            break;
+                        }
+                        self.expect_punct(",", "expected `,` or `>` in type arguments")?;
+                    }
+                }
+                continue;
+            } else {
+                // A parameter name.
+                break;
+            }
+            self.pos += 1;
+        }
+        if words.is_empty() {
+            return Err("expected a type name");
+        }
+        Ok(CType {
+            kind: CTypeKind::Named {
+                name: words.join(" "),
+                arguments,
+            },
+            qualifiers,
+            nullability,
+        })
+    }
+
+    /// The qualifiers and nullability after a `*` or `^`.
+    fn parse_pointer_qualifiers(&mut self, ty: &mut CType) {
+        while let Some(Token::Word(word)) = self.peek() {
+            if let Some(found) = TypeNullability::from_keyword(word) {
+                ty.nullability = Some(found);
+            } else if !ty.qualifiers.add(word) {
+                break;
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// The (abstract) declarator that applies pointers, arrays and
+    /// functions to `base`, e.g. `* const` or `(^)(int)`.
+    fn parse_declarator(&mut self, base: CType, allow_name: bool) -> Result<CType, &'static str> {
+        let mut ty = base;
+        // Blocks are only applied once the function suffix has been parsed,
+        // so `^` is only allowed inside parentheses.
+        while self.eat_punct("*") {
+            self.deepen()?;
+            ty = CType::pointer(ty);
+            self.parse_pointer_qualifiers(&mut ty);
+        }
+
+        // A parenthesized declarator applies to the type with the suffixes,
+        // so skip it for now.
+        let mut nested = None;
+        if self.peek_punct("(")
+            && matches!(
+                self.tokens.get(self.pos + 1),
+                Some(Token::Punct("*" | "^" | "("))
+            )
+        {
+            self.pos += 1;
+            let start = self.pos;
+            let mut depth = 1;
+            while depth > 0 {
+                match self.peek() {
+                    Some(Token::Punct("(")) => depth += 1,
+                    Some(Token::Punct(")")) => depth -= 1,
+                    Some(_) => {}
+                    None => return Err("expected `)`"),
+                }
+                self.pos += 1;
+            }
+            nested = Some(start);
+        } else if let Some(Token::Word(_)) = self.peek() {
+            if !allow_name {
+                return Err("unexpected name");
+            }
+            self.pos += 1;
+        }
+
+        let mut suffixes = Vec::new();
+        loop {
+            if self.peek_punct("[") || self.peek_punct("(") {
+                self.deepen()?;
+            }
+            if self.eat_punct("[") {
+                let size = match self.peek() {
+                    Some(Token::Number(size)) => {
+                        self.pos += 1;
+                        Some(size)
+                    }
+                    _ => None,
+                };
+                self.expect_punct("]", "expected `]`")?;
+                suffixes.push(Suffix::Array(size));
+            } else if self.eat_punct("(") {
+                let (parameters, variadic) = self.parse_parameters()?;
+                suffixes.push(Suffix::Function(parameters, variadic));
+            } else {
+                break;
+            }
+        }
+        // `int [2][3]` is an array of two arrays of three ints.
+        for suffix in suffixes.into_iter().rev() {
+            ty = match suffix {
+                Suffix::Array(size) => CType::new(CTypeKind::Array {
+                    element: Box::new(ty),
+                    size,
+                }),
+                Suffix::Function(parameters, variadic) => {
+                    CType::new(CTypeKind::Function(FunctionType {
+                        result: Box::new(ty),
+                        parameters,
+                        variadic,
+                    }))
+                }
+            };
+        }
+
+        if let Some(start) = nested {
+            self.deepen()?;
+            let end = self.pos;
+            self.pos = start;
+            ty = if self.eat_punct("^") {
+                let CTypeKind::Function(function) = ty.kind else {
+                    return Err("expected a parameter list after a block pointer");
+                };
+                let mut block = CType::new(CTypeKind::Block(function));
+                self.parse_pointer_qualifiers(&mut block);
+                self.parse_declarator(block, allow_name)?
+            } else {
+                self.parse_declarator(ty, allow_name)?
+            };
+            self.expect_punct(")", "expected `)`")?;
+            self.pos = end;
+        }
+        Ok(ty)
+    }
+
+    /// The parameters of a function, after the opening parenthesis.
+    fn parse_parameters(&mut self) -> Result<(Vec<CType>, bool), &'static str> {
+        let mut parameters = Vec::new();
+        if self.eat_punct(")") {
+            return Ok((parameters, false));
+        }
+        if self.peek() == Some(Token::Word("void"))
+            && self.tokens.get(self.pos + 1) == Some(&Token::Punct(")"))
+        {
+            self.pos += 2;
+            return Ok((parameters, false));
+        }
+        loop {
+            if self.eat_punct("...") {
+                self.expect_punct(")", "expected `)` after `...`")?;
+                return Ok((parameters, true));
+            }
+            parameters.push(self.parse_type(true)?);
+            if self.eat_punct(")") {
+                return Ok((parameters, false));
+            }
+            self.expect_punct(",", "expected `,` or `)` in parameter list")?;
+        }
+    }
+}
+
+enum Suffix {
+    Array(Option<u64>),
+    Function(Vec<CType>, bool),
+}
+
+fn parse(s: &str) -> Result<CType, &'static str> {
+    let mut parser = Parser {
+        tokens: tokenize(s)?,
+        pos: 0,
+        depth: 0,
+    };
+    let ty = parser.parse_type(false)?;
+    if parser.pos != parser.tokens.len() {
+        return Err("unexpected trailing input");
+    }
+    Ok(ty)
+}
+
+impl FromStr for CType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map_err(|reason| {
+            Error::new(
+                ErrorKind::InvalidValue,
+                format!("invalid C type `{s}`: {reason}"),
+            )
+        })
+    }
+}
+
+/// The qualifiers and nullability, each preceded by a space.
+fn qualifier_suffix(ty: &CType) -> String {
+    let mut s = String::new();
+    for keyword in ty.qualifiers.keywords() {
+        s.push(' ');
+        s.push_str(keyword);
+    }
+    if let Some(nullability) = ty.nullability {
+        s.push(' ');
+        s.push_str(nullability.keyword());
+    }
+    s
+}
+
+fn write_parameters(f: &mut fmt::Formatter<'_>, function: &FunctionType) -> fmt::Result {
+    if function.parameters.is_empty() && !function.variadic {
+        return f.write_str("(void)");
+    }
+    f.write_str("(")?;
+    for (i, parameter) in function.parameters.iter().enumerate() {
+        if i != 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{parameter}")?;
+    }
+    if function.variadic {
+        if !function.parameters.is_empty() {
+            f.write_str(", ")?;
+        }
+        f.write_str("...")?;
+    }
+    f.write_str(")")
+}
+
+/// Prepends a `*` or `^` with its qualifiers to a declarator, e.g. `**` or
+/// `* _Nonnull *`.
+fn join_pointer(token: String, inner: String) -> String {
+    if inner.is_empty() || token.ends_with(['*', '^']) {
+        token + &inner
+    } else {
+        format!("{token} {inner}")
+    }
+}
+
+/// Writes `ty` with the declarator `inner`, which was built from the
+/// types that contain `ty`.
+fn write_type(f: &mut fmt::Formatter<'_>, ty: &CType, inner: String) -> fmt::Result {
+    let wrap = |inner: String| {
+        if inner.starts_with(['*', '^']) {
+            format!("({inner})")
+        } else {
+            inner
+        }
+    };
+    match &ty.kind {
+        CTypeKind::Named { name, arguments } => {
+            // Written as e.g. `const int` rather than `int const`.
+            for keyword in ty.qualifiers.keywords() {
+                write!(f, "{keyword} ")?;
+            }
+            f.write_str(name)?;
+            if !arguments.is_empty() {
+                f.write_str("<")?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{argument}")?;
+                }
+                f.write_str(">")?;
+            }
+            if let Some(nullability) = ty.nullability {
+                write!(f, " {}", nullability.keyword())?;
+            }
+            if !inner.is_empty() {
+                write!(f, " {inner}")?;
+            }
+            Ok(())
+        }
+        CTypeKind::Pointer(pointee) => {
+            let inner = join_pointer(format!("*{}", qualifier_suffix(ty)), inner);
+            write_type(f, pointee, inner)
+        }
+        CTypeKind::Block(function) => {
+            let inner = join_pointer(format!("^{}", qualifier_suffix(ty)), inner);
+            write_type(
+                f,
+                &function.result,
+                format!("({inner}){}", Parameters(function)),
+            )
+        }
+        CTypeKind::Function(function) => write_type(
+            f,
+            &function.result,
+            format!("{}{}", wrap(inner), Parameters(function)),
+        ),
+        CTypeKind::Array { element, size } => {
+            let size = size.map(|size| size.to_string()).unwrap_or_default();
+            write_type(f, element, format!("{}[{size}]", wrap(inner)))
+        }
+    }
+}
+
+struct Parameters<'a>(&'a FunctionType);
+
+impl fmt::Display for Parameters<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_parameters(f, self.0)
+    }
+}
+
+impl fmt::Display for CType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type(f, self, String::new())
+    }
+}
@@ -7,14 +7,14 @@
 use std::path::Path;
 
 use crate::{
-    ApiNotes, Class, Data, Function, General, Global, Kind, Map, Method, MethodKey, Nullability,
-    Parameter, Property, PropertyKey, Protocol, Tag, Version,
+    ApiNotes, Availability, Class, Data, Function, General, Global, Kind, Map, Method, MethodKey,
+    Nullability, Parameter, Property, PropertyKey, Protocol, RetainCountConvention, Tag, Version,
 };
 
 impl MethodKey {
     fn new(selector: &str, kind: Kind) -> Self {
         Self {
-            selector: selector.into(),
+            selector: selector.parse().expect("valid selector"),
             kind,
         }
     }
@@ -36,62 +36,19 @@ fn assert_eq_to_file(expected: ApiNotes, name: &str) {
         .join(name);
 
     let actual = ApiNotes::from_path(&path).expect("ApiNotes::from_path");
-    if expected != actual {
-        if expected.swift_versions != actual.swift_versions {
-            panic!(
-                "expected swift version: {:#?}\n\ngot {:#?}",
-                expected.swift_versions, actual.swift_versions
-            );
-        }
-        if expected.data != actual.data {
-            if expected.data.classes != actual.data.classes {
-                panic!(
-                    "expected classes: {:#?}\n\ngot {:#?}",
-                    expected.data.classes, actual.data.classes
-                );
-            }
-            if expected.data.protocols != actual.data.protocols {
-                panic!(
-                    "expected protocols: {:#?}\n\ngot {:#?}",
-                    expected.data.protocols, actual.data.protocols
-                );
-            }
-            if expected.data.tags != actual.data.tags {
-                panic!(
-                    "expected tags: {:#?}\n\ngot {:#?}",
-                    expected.data.tags, actual.data.tags
-                );
-            }
-            if expected.data.typedefs != actual.data.typedefs {
-                panic!(
-                    "expected typedefs: {:#?}\n\ngot {:#?}",
-                    expected.data.typedefs, actual.data.typedefs
-                );
-            }
-            if expected.data.globals != actual.data.globals {
-                panic!(
-                    "expected globals: {:#?}\n\ngot {:#?}",
-                    expected.data.globals, actual.data.globals
-                );
-            }
-            if expected.data.enumerators != actual.data.enumerators {
-                panic!(
-                    "expected enumerators: {:#?}\n\ngot {:#?}",
-                    expected.data.enumerators, actual.data.enumerators
-                );
-            }
-            if expected.data.functions != actual.data.functions {
-                panic!(
-                    "expected functions: {:#?}\n\ngot {:#?}",
-                    expected.data.functions, actual.data.functions
-                );
-            }
-        }
+    let changes = expected.diff(&actual);
+    if !changes.is_empty() {
+        let changes: Vec<_> = changes.iter().map(ToString::to_string).collect();
         panic!(
-            "expected: {:#?}\n\ngot {:#?}",
-            expected.data.classes, actual.data.classes
+            "{name} differs from the expected notes:\n{}",
+            changes.join("\n")
         );
     }
+    assert_eq!(expected, actual);
+
+    let serialized = actual.to_string().expect("ApiNotes::to_string");
+    let roundtripped: ApiNotes = serialized.parse().expect("parse serialized");
+    assert_eq!(actual, roundtripped, "roundtrip through:\n{serialized}");
 }
 
 fn default<T: Default>() -> T {
@@ -189,11 +146,21 @@ fn simple_kit() {
                     methods: [
                         (
                             MethodKey::new("getOwnedToUnowned", Kind::Instance),
-                            Method { ..default() },
+                            Method {
+                                retain_count_convention: Some(
+                                    RetainCountConvention::NSReturnsNotRetained,
+                                ),
+                                ..default()
+                            },
                         ),
                         (
                             MethodKey::new("getUnownedToOwned", Kind::Instance),
-                            Method { ..default() },
+                            Method {
+                                retain_count_convention: Some(
+                                    RetainCountConvention::NSReturnsRetained,
+                                ),
+                                ..default()
+                            },
                         ),
                     ]
                     .into(),
@@ -225,45 +192,124 @@ fn simple_kit() {
             ]
             .into(),
             functions: [
-                ("getCFOwnedToUnowned".into(), Function { ..default() }),
-                ("getCFUnownedToOwned".into(), Function { ..default() }),
-                ("getCFOwnedToNone".into(), Function { ..default() }),
-                ("getObjCOwnedToUnowned".into(), Function { ..default() }),
-                ("getObjCUnownedToOwned".into(), Function { ..default() }),
+                (
+                    "getCFOwnedToUnowned".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::CFReturnsNotRetained),
+                        ..default()
+                    },
+                ),
+                (
+                    "getCFUnownedToOwned".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::CFReturnsRetained),
+                        ..default()
+                    },
+                ),
+                (
+                    "getCFOwnedToNone".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::None),
+                        ..default()
+                    },
+                ),
+                (
+                    "getObjCOwnedToUnowned".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::NSReturnsNotRetained),
+                        ..default()
+                    },
+                ),
+                (
+                    "getObjCUnownedToOwned".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::NSReturnsRetained),
+                        ..default()
+                    },
+                ),
                 (
                     "indirectGetCFOwnedToUnowned".into(),
                     Function {
-                        parameters: [(0, Parameter { ..default() })].into(),
+                        parameters: [(
+                            0,
+                            Parameter {
+                                retain_count_convention: Some(
+                                    RetainCountConvention::CFReturnsNotRetained,
+                                ),
+                                ..default()
+                            },
+                        )]
+                        .into(),
                         ..default()
                     },
                 ),
                 (
                     "indirectGetCFUnownedToOwned".into(),
                     Function {
-                        parameters: [(0, Parameter { ..default() })].into(),
+                        parameters: [(
+                            0,
+                            Parameter {
+                                retain_count_convention: Some(
+                                    RetainCountConvention::CFReturnsRetained,
+                                ),
+                                ..default()
+                            },
+                        )]
+                        .into(),
                         ..default()
                     },
                 ),
                 (
                     "indirectGetCFOwnedToNone".into(),
                     Function {
-                        parameters: [(0, Parameter { ..default() })].into(),
+                        parameters: [(
+                            0,
+                            Parameter {
+                                retain_count_convention: Some(RetainCountConvention::None),
+                                ..default()
+                            },
+                        )]
+                        .into(),
                         ..default()
                     },
                 ),
                 (
                     "indirectGetCFNoneToOwned".into(),
                     Function {
-                        parameters: [(0, Parameter { ..default() })].into(),
+                        parameters: [(
+                            0,
+                            Parameter {
+                                retain_count_convention: Some(
+                                    RetainCountConvention::CFReturnsNotRetained,
+                                ),
+                                ..default()
+                            },
+                        )]
+                        .into(),
                         ..default()
                     },
                 ),
                 (
                     "getCFAuditedToUnowned_DUMP".into(),
-                    Function { ..default() },
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::CFReturnsNotRetained),
+                        ..default()
+                    },
+                ),
+                (
+                    "getCFAuditedToOwned_DUMP".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::CFReturnsRetained),
+                        ..default()
+                    },
+                ),
+                (
+                    "getCFAuditedToNone_DUMP".into(),
+                    Function {
+                        retain_count_convention: Some(RetainCountConvention::None),
+                        ..default()
+                    },
                 ),
-                ("getCFAuditedToOwned_DUMP".into(), Function { ..default() }),
-                ("getCFAuditedToNone_DUMP".into(), Function { ..default() }),
             ]
             .into(),
             ..default()
@@ -288,7 +334,7 @@ fn some_kit() {
                                 MethodKey::new("transform:", Kind::Instance),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("anything but this".into()),
                                         ..default()
                                     },
@@ -310,7 +356,7 @@ fn some_kit() {
                                 MethodKey::new("implicitGetOnlyInstance", Kind::Instance),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("getter gone".into()),
                                         ..default()
                                     },
@@ -321,7 +367,7 @@ fn some_kit() {
                                 MethodKey::new("implicitGetOnlyClass", Kind::Class),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("getter gone".into()),
                                         ..default()
                                     },
@@ -332,7 +378,7 @@ fn some_kit() {
                                 MethodKey::new("implicitGetSetInstance", Kind::Instance),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("getter gone".into()),
                                         ..default()
                                     },
@@ -343,7 +389,7 @@ fn some_kit() {
                                 MethodKey::new("implicitGetSetClass", Kind::Class),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("getter gone".into()),
                                         ..default()
                                     },
@@ -354,7 +400,7 @@ fn some_kit() {
                                 MethodKey::new("setImplicitGetSetInstance:", Kind::Instance),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("setter gone".into()),
                                         ..default()
                                     },
@@ -365,7 +411,7 @@ fn some_kit() {
                                 MethodKey::new("setImplicitGetSetClass:", Kind::Class),
                                 Method {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("setter gone".into()),
                                         ..default()
                                     },
@@ -379,7 +425,7 @@ fn some_kit() {
                                 PropertyKey::new("intValue", Kind::Instance),
                                 Property {
                                     general: General {
-                                        availability: Some("none".into()),
+                                        availability: Some(Availability::None),
                                         availability_msg: Some("wouldn't work anyway".into()),
                                         ..default()
                                     },
@@ -419,7 +465,7 @@ fn some_kit() {
                     "B".into(),
                     Class {
                         general: General {
-                            availability: Some("none".into()),
+                            availability: Some(Availability::None),
                             availability_msg: Some("just don't".into()),
                             ..default()
                         },
@@ -517,7 +563,7 @@ fn some_kit() {
             ..default()
         },
         swift_versions: [(
-            Version::V3,
+            Version::new(3, 0, 0),
             Data {
                 classes: [(
                     "A".into(),
@@ -598,7 +644,7 @@ fn some_kit_private() {
                 "InternalProtocol".into(),
                 Protocol {
                     general: General {
-                        availability: Some("none".into()),
+                        availability: Some(Availability::None),
                         availability_msg: Some("not for you".into()),
                         ..default()
                     },
@@ -613,3 +659,40 @@ fn some_kit_private() {
 
     assert_eq_to_file(expected, "SomeKit_private.apinotes");
 }
+
+#[test]
+fn some_kit_swift_versions() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("clang_tests")
+        .join("SomeKit.apinotes");
+    let mut notes = ApiNotes::from_path(&path).expect("ApiNotes::from_path");
+    let transform = MethodKey::new("transform:integer:", Kind::Instance);
+
+    let data = notes.for_swift_version(Version::new(4, 0, 0));
+    assert_eq!(data, notes.data);
+
+    let data = notes.for_swift_version(Version::new(3, 0, 0));
+    let class = &data.classes["A"];
+    assert_eq!(
+        class.methods[&transform].nullability_of_ret,
+        Some(Nullability::Optional)
+    );
+    // Members that are not overridden are kept.
+    assert!(class
+        .methods
+        .contains_key(&MethodKey::new("transform:", Kind::Instance)));
+    assert!(class
+        .properties
+        .contains_key(&PropertyKey::new("explicitNonnullInstance", Kind::Instance)));
+    assert!(class.properties.contains_key(&PropertyKey {
+        name: "nonnullABoth".into(),
+        kind: None,
+    }));
+
+    // `Type` overrides `Nullability`.
+    let global = notes.data.globals.get_mut("global_int_ptr").unwrap();
+    global.nullability = Some(Nullability::Nonnull);
+    let data = notes.for_swift_version(Version::new(3, 0, 0));
+    assert_eq!(data.globals["global_int_ptr"].nullability, None);
+}
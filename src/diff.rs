@@ -0,0 +1,231 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::{path, ApiNotes};
+
+/// A difference between two [`ApiNotes`], see [`ApiNotes::diff`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Change {
+    /// Whether the entry or attribute was added, removed or changed.
+    pub kind: ChangeKind,
+    /// The entry or attribute that changed, e.g.
+    /// `Classes[A].Methods[-initWithA:].Parameters[0].Nullability`.
+    ///
+    /// Entries are identified like in [`Diagnostic::path`], i.e. instance
+    /// members are prefixed with `-` and class members with `+`.
+    ///
+    /// [`Diagnostic::path`]: crate::Diagnostic::path
+    pub path: String,
+    /// The old value of an attribute, or `None` for added attributes and
+    /// for entries.
+    pub old: Option<String>,
+    /// The new value of an attribute, or `None` for removed attributes and
+    /// for entries.
+    pub new: Option<String>,
+}
+
+/// The kind of a [`Change`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The entry or attribute only exists in the new notes.
+    Added,
+    /// The entry or attribute only exists in the old notes.
+    Removed,
+    /// The attribute has a different value in the new notes.
+    Changed,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        };
+        write!(f, "{sign} {}", self.path)?;
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, ": {old} -> {new}"),
+            (Some(value), None) | (None, Some(value)) => write!(f, ": {value}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl ApiNotes {
+    /// Compare the notes with `new` entry by entry.
+    ///
+    /// Entries (classes, methods, properties, parameters, `SwiftVersions`
+    /// sections, ...) that were added or removed are reported as a whole,
+    /// while for entries that exist in both, each added, removed or changed
+    /// attribute is reported.
+    ///
+    /// ```
+    /// use apinotes::{ApiNotes, ChangeKind};
+    ///
+    /// let old: ApiNotes = "Name: A\nFunctions:\n  - Name: f\n    SwiftName: f()\n".parse().unwrap();
+    /// let new: ApiNotes = "Name: A\nFunctions:\n  - Name: f\n    SwiftName: g()\n".parse().unwrap();
+    /// let changes = old.diff(&new);
+    /// assert_eq!(changes.len(), 1);
+    /// assert_eq!(changes[0].kind, ChangeKind::Changed);
+    /// assert_eq!(changes[0].to_string(), "~ Functions[f].SwiftName: f() -> g()");
+    /// ```
+    pub fn diff(&self, new: &ApiNotes) -> Vec<Change> {
+        let old = to_value(self);
+        let new = to_value(new);
+        let mut changes = Vec::new();
+        diff_mappings(&old, &new, "", &mut changes);
+        changes
+    }
+}
+
+fn to_value(notes: &ApiNotes) -> Mapping {
+    match serde_yaml::to_value(notes).expect("API notes can always be serialized") {
+        Value::Mapping(mapping) => mapping,
+        _ => unreachable!("API notes are serialized as a mapping"),
+    }
+}
+
+pub(crate) fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.into()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+fn diff_mappings(old: &Mapping, new: &Mapping, path: &str, changes: &mut Vec<Change>) {
+    for (key, old_value) in old {
+        let path = join(path, key.as_str().unwrap_or_default());
+        match new.get(key) {
+            Some(new_value) => diff_values(old_value, new_value, &path, changes),
+            None => removed(old_value, path, changes),
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            added(
+                new_value,
+                join(path, key.as_str().unwrap_or_default()),
+                changes,
+            );
+        }
+    }
+}
+
+fn diff_values(old: &Value, new: &Value, path: &str, changes: &mut Vec<Change>) {
+    if old == new {
+        return;
+    }
+    match (entries(old), entries(new)) {
+        (Some(old), Some(new)) => {
+            for (label, old_entry) in &old {
+                let entry_path = format!("{path}[{label}]");
+                match new.iter().find(|(new_label, _)| new_label == label) {
+                    Some((_, new_entry)) => {
+                        diff_mappings(old_entry, new_entry, &entry_path, changes)
+                    }
+                    None => changes.push(entry_change(ChangeKind::Removed, entry_path)),
+                }
+            }
+            for (label, _) in &new {
+                if !old.iter().any(|(old_label, _)| old_label == label) {
+                    changes.push(entry_change(ChangeKind::Added, format!("{path}[{label}]")));
+                }
+            }
+        }
+        _ => match (old, new) {
+            (Value::Mapping(old), Value::Mapping(new)) => diff_mappings(old, new, path, changes),
+            _ => changes.push(Change {
+                kind: ChangeKind::Changed,
+                path: path.into(),
+                old: Some(display(old)),
+                new: Some(display(new)),
+            }),
+        },
+    }
+}
+
+fn added(value: &Value, path: String, changes: &mut Vec<Change>) {
+    match nonempty_entries(value) {
+        Some(entries) => changes.extend(
+            entries
+                .into_iter()
+                .map(|(label, _)| entry_change(ChangeKind::Added, format!("{path}[{label}]"))),
+        ),
+        None => changes.push(Change {
+            kind: ChangeKind::Added,
+            path,
+            old: None,
+            new: Some(display(value)),
+        }),
+    }
+}
+
+fn removed(value: &Value, path: String, changes: &mut Vec<Change>) {
+    match nonempty_entries(value) {
+        Some(entries) => changes.extend(
+            entries
+                .into_iter()
+                .map(|(label, _)| entry_change(ChangeKind::Removed, format!("{path}[{label}]"))),
+        ),
+        None => changes.push(Change {
+            kind: ChangeKind::Removed,
+            path,
+            old: Some(display(value)),
+            new: None,
+        }),
+    }
+}
+
+fn entry_change(kind: ChangeKind, path: String) -> Change {
+    Change {
+        kind,
+        path,
+        old: None,
+        new: None,
+    }
+}
+
+/// The entries of a list of entries, e.g. `Classes` or `Parameters`, along
+/// with the label that identifies each of them.
+/// The entries of a value that is added or removed as a whole, unless there
+/// are none, so that e.g. adding `Nullability: []` is still reported.
+fn nonempty_entries(value: &Value) -> Option<Vec<(String, &Mapping)>> {
+    entries(value).filter(|entries| !entries.is_empty())
+}
+
+pub(crate) fn entries(value: &Value) -> Option<Vec<(String, &Mapping)>> {
+    value
+        .as_sequence()?
+        .iter()
+        .map(|entry| {
+            let entry = entry.as_mapping()?;
+            Some((label(entry)?, entry))
+        })
+        .collect()
+}
+
+fn label(entry: &Mapping) -> Option<String> {
+    path::label(|key| entry.get(key).map(display))
+}
+
+pub(crate) fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "~".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        Value::Sequence(items) => {
+            let items: Vec<_> = items.iter().map(display).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Mapping(_) | Value::Tagged(_) => serde_yaml::to_string(value)
+            .unwrap_or_default()
+            .trim_end()
+            .into(),
+    }
+}
@@ -0,0 +1,869 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use serde_yaml::Value;
+
+use crate::format::{is_plain_safe, write_double_quoted, write_scalar};
+use crate::{path, ApiNotes, Error, ErrorKind, Mark};
+
+/// A lossless, editable view of an API notes file.
+///
+/// Unlike [`ApiNotes`], this keeps the original text around, so comments,
+/// key ordering and formatting of regions that are not edited are preserved
+/// byte for byte when the document is written back out.
+///
+/// Nodes in the document are referred to with [`Location`]s, which are
+/// obtained by navigating from [`Document::root`].
+///
+/// Only the subset of YAML that API notes use is supported, that is block
+/// mappings and sequences, single-line scalars and single-line flow
+/// sequences such as `[ N, S ]`.
+#[derive(Clone, Debug)]
+pub struct Document {
+    source: String,
+    root: Node,
+}
+
+/// The location of a node inside a [`Document`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Location {
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+impl Location {
+    fn join(&self, step: Step) -> Self {
+        let mut steps = self.steps.clone();
+        steps.push(step);
+        Self { steps }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    span: Range<usize>,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    /// A missing value, e.g. `Key:` followed by nothing.
+    Null,
+    Scalar {
+        value: String,
+        style: Style,
+    },
+    Mapping {
+        indent: usize,
+        entries: Vec<Entry>,
+    },
+    Sequence {
+        indent: usize,
+        items: Vec<Node>,
+    },
+    FlowSequence(Vec<Node>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Style {
+    Plain,
+    SingleQuoted,
+    DoubleQuoted,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    key: String,
+    /// The offset of the start of the key.
+    start: usize,
+    /// The offset just after the `:`.
+    colon: usize,
+    value: Node,
+}
+
+impl Node {
+    fn scalar(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Scalar { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Entry> {
+        match &self.kind {
+            NodeKind::Mapping { entries, .. } => entries.iter().find(|entry| entry.key == key),
+            _ => None,
+        }
+    }
+
+    fn items(&self) -> &[Node] {
+        match &self.kind {
+            NodeKind::Sequence { items, .. } => items,
+            NodeKind::FlowSequence(items) => items,
+            _ => &[],
+        }
+    }
+}
+
+impl Document {
+    /// Parse a document from the contents of an API notes file.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let root = Parser::new(source).parse()?;
+        Ok(Self {
+            source: source.into(),
+            root,
+        })
+    }
+
+    /// The current text of the document, including any edits.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Convert the document into the current text.
+    pub fn into_string(self) -> String {
+        self.source
+    }
+
+    /// Parse the current text of the document into [`ApiNotes`].
+    pub fn to_api_notes(&self) -> Result<ApiNotes, Error> {
+        self.source.parse()
+    }
+
+    /// The location of the top-level mapping.
+    pub fn root(&self) -> Location {
+        Location::default()
+    }
+
+    fn node(&self, at: &Location) -> Option<&Node> {
+        let mut node = &self.root;
+        for step in &at.steps {
+            node = match step {
+                Step::Key(key) => &node.get(key)?.value,
+                Step::Index(index) => node.items().get(*index)?,
+            };
+        }
+        Some(node)
+    }
+
+    /// The location of the value of `key` in the mapping at `at`.
+    pub fn get(&self, at: &Location, key: &str) -> Option<Location> {
+        self.node(at)?.get(key)?;
+        Some(at.join(Step::Key(key.into())))
+    }
+
+    /// The location of the item in the sequence under `key` in the mapping
+    /// at `at`, whose scalar fields match all of `fields`.
+    ///
+    /// For example, to find a method in a class:
+    ///
+    /// ```
+    /// # let doc = apinotes::Document::parse("Name: Foo\nClasses:\n  - Name: A\n    Methods:\n      - Selector: 'init'\n        MethodKind: Instance\n").unwrap();
+    /// let class = doc.find(&doc.root(), "Classes", &[("Name", "A")]).unwrap();
+    /// let method = doc.find(&class, "Methods", &[("Selector", "init"), ("MethodKind", "Instance")]);
+    /// assert!(method.is_some());
+    /// ```
+    pub fn find(&self, at: &Location, key: &str, fields: &[(&str, &str)]) -> Option<Location> {
+        let items = self.node(at)?.get(key)?.value.items();
+        let index = items.iter().position(|item| {
+            fields.iter().all(|(field, expected)| {
+                item.get(field).and_then(|entry| entry.value.scalar()) == Some(expected)
+            })
+        })?;
+        Some(at.join(Step::Key(key.into())).join(Step::Index(index)))
+    }
+
+    /// The position of the entry or attribute at `path` in the source.
+    ///
+    /// `path` uses the syntax of e.g. [`Diagnostic::path`], so this can be
+    /// used to point to the source of diagnostics, changes and conflicts.
+    ///
+    /// ```
+    /// # let doc = apinotes::Document::parse("Name: Foo\nClasses:\n  - Name: A\n    Methods:\n      - Selector: 'init'\n        MethodKind: Instance\n").unwrap();
+    /// let mark = doc.locate("Classes[A].Methods[-init].MethodKind").unwrap();
+    /// assert_eq!((mark.line, mark.column), (6, 9));
+    /// ```
+    ///
+    /// [`Diagnostic::path`]: crate::Diagnostic::path
+    pub fn locate(&self, path: &str) -> Option<Mark> {
+        let mut node = &self.root;
+        let mut offset = 0;
+        for segment in path::parse(path)? {
+            let entry = node.get(segment.key)?;
+            node = &entry.value;
+            offset = entry.start;
+            if let Some(label) = segment.label {
+                node = node.items().iter().find(|item| {
+                    path::label(|key| Some(item.get(key)?.value.scalar()?.to_string())).as_deref()
+                        == Some(label)
+                })?;
+                offset = node.span.start;
+            }
+        }
+        Some(Mark::from_offset(&self.source, offset))
+    }
+
+    /// The number of items in the sequence at `at`.
+    pub fn len(&self, at: &Location) -> usize {
+        self.node(at).map(|node| node.items().len()).unwrap_or(0)
+    }
+
+    /// The location of the item at `index` in the sequence at `at`.
+    pub fn index(&self, at: &Location, index: usize) -> Option<Location> {
+        self.node(at)?.items().get(index)?;
+        Some(at.join(Step::Index(index)))
+    }
+
+    /// The value of the scalar at `at`.
+    pub fn scalar(&self, at: &Location) -> Option<&str> {
+        self.node(at)?.scalar()
+    }
+
+    /// Set `key` in the mapping at `at` to the scalar `value`.
+    ///
+    /// If the key already exists, only its value is replaced, otherwise a
+    /// new entry is added after the last entry in the mapping. Strings are
+    /// quoted where needed to be read back as strings, e.g. `"true"`, while
+    /// booleans and numbers are written as-is.
+    ///
+    /// ```
+    /// # let mut doc = apinotes::Document::parse("Name: Foo\nFunctions:\n  - Name: f\n").unwrap();
+    /// let function = doc.find(&doc.root(), "Functions", &[("Name", "f")]).unwrap();
+    /// doc.set(&function, "SwiftName", "g()").unwrap();
+    /// doc.set(&function, "SwiftPrivate", true).unwrap();
+    /// assert!(doc.as_str().ends_with("SwiftName: g()\n    SwiftPrivate: true\n"));
+    /// ```
+    pub fn set(&mut self, at: &Location, key: &str, value: impl Into<Value>) -> Result<(), Error> {
+        let value = value.into();
+        let node = self.mapping(at)?;
+        if value.is_sequence() || value.is_mapping() {
+            return Err(self.error(node.span.start, "expected a scalar value"));
+        }
+        let (range, text) = match node.get(key) {
+            Some(entry) => match &entry.value.kind {
+                NodeKind::Scalar { style, .. } => {
+                    (entry.value.span.clone(), format_scalar(&value, *style))
+                }
+                NodeKind::Null => (
+                    entry.colon..entry.colon,
+                    format!(" {}", format_scalar(&value, Style::Plain)),
+                ),
+                _ => return Err(self.error(entry.value.span.start, "expected a scalar value")),
+            },
+            None => {
+                let (offset, indent) = self.append_point(node);
+                let text = format!(
+                    "{}{}{}: {}",
+                    self.newline(),
+                    " ".repeat(indent),
+                    format_key(key),
+                    format_scalar(&value, Style::Plain)
+                );
+                (offset..offset, text)
+            }
+        };
+        self.replace(range, &text)
+    }
+
+    /// Remove `key` and its value from the mapping at `at`.
+    ///
+    /// Returns `false` if the key did not exist.
+    pub fn remove(&mut self, at: &Location, key: &str) -> Result<bool, Error> {
+        let node = self.mapping(at)?;
+        let entry = match node.get(key) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        let start = self.line_start(entry.start);
+        if self.source[start..entry.start].trim() != "" {
+            return Err(self.error(
+                entry.start,
+                "cannot remove an entry that does not start on its own line",
+            ));
+        }
+        let end = entry.value.span.end.max(entry.colon);
+        let end = self.source[end..]
+            .find('\n')
+            .map(|i| end + i + 1)
+            .unwrap_or(self.source.len());
+        self.replace(start..end, "")?;
+        Ok(true)
+    }
+
+    /// Append a new mapping with the scalar `fields` to the sequence under
+    /// `key` in the mapping at `at`, creating the sequence if needed.
+    ///
+    /// The values are written like in [`set`][Self::set].
+    ///
+    /// Returns the location of the new item.
+    pub fn push(
+        &mut self,
+        at: &Location,
+        key: &str,
+        fields: &[(&str, Value)],
+    ) -> Result<Location, Error> {
+        let node = self.mapping(at)?;
+        if fields
+            .iter()
+            .any(|(_, value)| value.is_sequence() || value.is_mapping())
+        {
+            return Err(self.error(node.span.start, "expected scalar values"));
+        }
+        let parent_indent = match &node.kind {
+            NodeKind::Mapping { indent, .. } => *indent,
+            _ => unreachable!("not a mapping"),
+        };
+
+        let newline = self.newline();
+        let item = |indent: usize| {
+            let mut text = String::new();
+            for (i, (field, value)) in fields.iter().enumerate() {
+                text.push_str(newline);
+                text.push_str(&" ".repeat(indent));
+                text.push_str(if i == 0 { "- " } else { "  " });
+                text.push_str(&format_key(field));
+                text.push_str(": ");
+                text.push_str(&format_scalar(value, Style::Plain));
+            }
+            if fields.is_empty() {
+                text.push_str(newline);
+                text.push_str(&" ".repeat(indent));
+                text.push_str("- {}");
+            }
+            text
+        };
+
+        let (range, text, index) = match node.get(key) {
+            Some(entry) => match &entry.value.kind {
+                NodeKind::Sequence { indent, items } => {
+                    let offset = self.line_end(entry.value.span.end);
+                    (offset..offset, item(*indent), items.len())
+                }
+                NodeKind::Null => (entry.colon..entry.colon, item(parent_indent + 2), 0),
+                NodeKind::FlowSequence(items) if items.is_empty() => (
+                    entry.colon..entry.value.span.end,
+                    item(parent_indent + 2),
+                    0,
+                ),
+                _ => return Err(self.error(entry.value.span.start, "expected a block sequence")),
+            },
+            None => {
+                let (offset, indent) = self.append_point(node);
+                let text = format!(
+                    "{newline}{}{}:{}",
+                    " ".repeat(indent),
+                    format_key(key),
+                    item(indent + 2)
+                );
+                (offset..offset, text, 0)
+            }
+        };
+        self.replace(range, &text)?;
+        Ok(at.join(Step::Key(key.into())).join(Step::Index(index)))
+    }
+
+    fn mapping(&self, at: &Location) -> Result<&Node, Error> {
+        match self.node(at) {
+            Some(
+                node @ Node {
+                    kind: NodeKind::Mapping { .. },
+                    ..
+                },
+            ) => Ok(node),
+            Some(node) => Err(self.error(node.span.start, "expected a mapping")),
+            None => Err(self.error(0, "location does not exist in the document")),
+        }
+    }
+
+    /// The offset and indentation at which to add a new entry to a mapping.
+    fn append_point(&self, node: &Node) -> (usize, usize) {
+        match &node.kind {
+            NodeKind::Mapping { indent, entries } => {
+                let end = entries
+                    .last()
+                    .map(|entry| entry.value.span.end.max(entry.colon))
+                    .unwrap_or(node.span.end);
+                (self.line_end(end), *indent)
+            }
+            _ => unreachable!("not a mapping"),
+        }
+    }
+
+    /// The line break used by the document, so that new lines match it.
+    fn newline(&self) -> &'static str {
+        match self.source.find('\n') {
+            Some(i) if self.source[..i].ends_with('\r') => "\r\n",
+            _ => "\n",
+        }
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.source[..offset]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+
+    /// The offset of the end of the line containing `offset`, excluding the
+    /// line break.
+    fn line_end(&self, offset: usize) -> usize {
+        let end = self.source[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(self.source.len());
+        if end > 0 && self.source.as_bytes()[end - 1] == b'\r' {
+            end - 1
+        } else {
+            end
+        }
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) -> Result<(), Error> {
+        let mut source = self.source.clone();
+        source.replace_range(range, text);
+        self.root = Parser::new(&source).parse()?;
+        self.source = source;
+        Ok(())
+    }
+
+    fn error(&self, offset: usize, message: &str) -> Error {
+        Error::from_document(&self.source, offset, ErrorKind::InvalidEdit, message)
+    }
+}
+
+impl FromStr for Document {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn format_key(key: &str) -> String {
+    format_scalar(&key.into(), Style::Plain)
+}
+
+/// Write a scalar, keeping the quoting style of a string where possible.
+fn format_scalar(value: &Value, style: Style) -> String {
+    let mut text = String::new();
+    match value {
+        Value::String(s) => match style {
+            Style::Plain if is_plain_safe(s, false) => text.push_str(s),
+            Style::SingleQuoted if !s.chars().any(char::is_control) => {
+                text = format!("'{}'", s.replace('\'', "''"));
+            }
+            _ => write_double_quoted(&mut text, s),
+        },
+        value => write_scalar(&mut text, value, false, false),
+    }
+    text
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Line {
+    start: usize,
+    end: usize,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    lines: Vec<Line>,
+    /// The index of the next line to parse.
+    line: usize,
+}
+
+type ParseResult<T> = Result<T, Error>;
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for line in source.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            lines.push(Line {
+                start,
+                end: start + content.len(),
+            });
+            start += line.len();
+        }
+        Self {
+            source,
+            lines,
+            line: 0,
+        }
+    }
+
+    fn error(&self, offset: usize, message: &str) -> Error {
+        Error::from_document(self.source, offset, ErrorKind::Syntax, message)
+    }
+
+    fn text(&self, line: usize) -> &'a str {
+        let Line { start, end } = self.lines[line];
+        &self.source[start..end]
+    }
+
+    fn indent(&self, line: usize) -> usize {
+        let text = self.text(line);
+        text.len() - text.trim_start_matches(' ').len()
+    }
+
+    fn is_blank(&self, line: usize) -> bool {
+        let text = self.text(line).trim_start_matches(' ');
+        text.is_empty()
+            || text.starts_with('#')
+            || text.trim_end() == "---"
+            || text.trim_end() == "..."
+            || (line == 0 && text.starts_with('%'))
+    }
+
+    fn next_content_line(&self, from: usize) -> Option<usize> {
+        (from..self.lines.len()).find(|&line| !self.is_blank(line))
+    }
+
+    fn is_sequence_item(text: &str) -> bool {
+        text == "-" || text.starts_with("- ") || text.starts_with("-\t")
+    }
+
+    fn parse(mut self) -> ParseResult<Node> {
+        let line = match self.next_content_line(0) {
+            Some(line) => line,
+            None => {
+                return Ok(Node {
+                    span: 0..0,
+                    kind: NodeKind::Mapping {
+                        indent: 0,
+                        entries: Vec::new(),
+                    },
+                })
+            }
+        };
+        let text = self.text(line);
+        if text.starts_with([' ', '\t']) {
+            return Err(self.error(self.lines[line].start, "unexpected indentation"));
+        }
+        let root = self.parse_block(line, 0)?;
+        if let Some(line) = self.next_content_line(self.line) {
+            return Err(self.error(self.lines[line].start, "unexpected content"));
+        }
+        Ok(root)
+    }
+
+    /// Parse a block node starting at the given column of the given line.
+    fn parse_block(&mut self, line: usize, col: usize) -> ParseResult<Node> {
+        let text = &self.text(line)[col..];
+        if text.starts_with('\t') {
+            return Err(self.error(self.lines[line].start + col, "tabs are not allowed"));
+        }
+        if Self::is_sequence_item(text) {
+            self.parse_sequence(line, col)
+        } else if self.parse_key(line, col)?.is_some() {
+            self.parse_mapping(line, col)
+        } else {
+            let node = self.parse_inline(line, col)?;
+            self.line = line + 1;
+            Ok(node)
+        }
+    }
+
+    fn parse_sequence(&mut self, mut line: usize, indent: usize) -> ParseResult<Node> {
+        let start = self.lines[line].start + indent;
+        let mut items = Vec::new();
+        loop {
+            let text = &self.text(line)[indent + 1..];
+            let rest = text.trim_start_matches([' ', '\t']);
+            let col = indent + 1 + text.len() - rest.len();
+            let item = if rest.is_empty() || rest.starts_with('#') {
+                match self.next_content_line(line + 1) {
+                    Some(next) if self.indent(next) > indent => {
+                        self.parse_block(next, self.indent(next))?
+                    }
+                    _ => {
+                        self.line = line + 1;
+                        let offset = self.lines[line].start + indent + 1;
+                        Node {
+                            span: offset..offset,
+                            kind: NodeKind::Null,
+                        }
+                    }
+                }
+            } else {
+                self.parse_block(line, col)?
+            };
+            items.push(item);
+
+            match self.next_content_line(self.line) {
+                Some(next) if self.indent(next) == indent => {
+                    if !Self::is_sequence_item(&self.text(next)[indent..]) {
+                        break;
+                    }
+                    line = next;
+                }
+                Some(next) if self.indent(next) > indent => {
+                    return Err(self.error(self.lines[next].start, "unexpected indentation"));
+                }
+                _ => break,
+            }
+        }
+        let end = items.last().map(|item| item.span.end).unwrap_or(start);
+        Ok(Node {
+            span: start..end,
+            kind: NodeKind::Sequence { indent, items },
+        })
+    }
+
+    fn parse_mapping(&mut self, mut line: usize, indent: usize) -> ParseResult<Node> {
+        let start = self.lines[line].start + indent;
+        let mut entries: Vec<Entry> = Vec::new();
+        loop {
+            let (key, after_colon) = match self.parse_key(line, indent)? {
+                Some(key) => key,
+                None => {
+                    return Err(self.error(self.lines[line].start + indent, "expected a key"));
+                }
+            };
+            let key_start = self.lines[line].start + indent;
+            let colon = self.lines[line].start + after_colon;
+            if entries.iter().any(|entry| entry.key == key) {
+                return Err(Error::from_document(
+                    self.source,
+                    key_start,
+                    ErrorKind::DuplicateEntry,
+                    "duplicate key",
+                ));
+            }
+
+            let text = &self.text(line)[after_colon..];
+            let rest = text.trim_start_matches([' ', '\t']);
+            let value = if rest.is_empty() || rest.starts_with('#') {
+                match self.next_content_line(line + 1) {
+                    Some(next) if self.indent(next) > indent => {
+                        self.parse_block(next, self.indent(next))?
+                    }
+                    Some(next)
+                        if self.indent(next) == indent
+                            && Self::is_sequence_item(&self.text(next)[indent..]) =>
+                    {
+                        self.parse_sequence(next, indent)?
+                    }
+                    _ => {
+                        self.line = line + 1;
+                        Node {
+                            span: colon..colon,
+                            kind: NodeKind::Null,
+                        }
+                    }
+                }
+            } else {
+                let col = after_colon + text.len() - rest.len();
+                let node = self.parse_inline(line, col)?;
+                self.line = line + 1;
+                node
+            };
+            entries.push(Entry {
+                key,
+                start: key_start,
+                colon,
+                value,
+            });
+
+            match self.next_content_line(self.line) {
+                Some(next) if self.indent(next) == indent => {
+                    if Self::is_sequence_item(&self.text(next)[indent..]) {
+                        return Err(self.error(self.lines[next].start, "unexpected sequence item"));
+                    }
+                    line = next;
+                }
+                Some(next) if self.indent(next) > indent => {
+                    return Err(self.error(self.lines[next].start, "unexpected indentation"));
+                }
+                _ => break,
+            }
+        }
+        let end = entries
+            .last()
+            .map(|entry| entry.value.span.end.max(entry.colon))
+            .unwrap_or(start);
+        Ok(Node {
+            span: start..end,
+            kind: NodeKind::Mapping { indent, entries },
+        })
+    }
+
+    /// Try to parse a mapping key at the given position, returning the key
+    /// and the column just after the `:`.
+    fn parse_key(&self, line: usize, col: usize) -> ParseResult<Option<(String, usize)>> {
+        let text = &self.text(line)[col..];
+        let (key, after) = match text.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let offset = self.lines[line].start + col;
+                let (key, len) = self.parse_quoted(text, quote, offset)?;
+                let rest = &text[len..];
+                let trimmed = rest.trim_start_matches(' ');
+                if !trimmed.starts_with(':') {
+                    return Ok(None);
+                }
+                (key, len + rest.len() - trimmed.len() + 1)
+            }
+            Some('[' | '{' | '#') | None => return Ok(None),
+            Some(_) => {
+                let bytes = text.as_bytes();
+                let mut found = None;
+                for (i, &b) in bytes.iter().enumerate() {
+                    if b == b'#' && i > 0 && bytes[i - 1] == b' ' {
+                        break;
+                    }
+                    if b == b':' && matches!(bytes.get(i + 1), None | Some(b' ' | b'\t')) {
+                        found = Some(i);
+                        break;
+                    }
+                }
+                match found {
+                    Some(i) => (text[..i].trim_end().to_string(), i + 1),
+                    None => return Ok(None),
+                }
+            }
+        };
+        Ok(Some((key, col + after)))
+    }
+
+    /// Parse a scalar or flow sequence that must end on the given line.
+    fn parse_inline(&self, line: usize, col: usize) -> ParseResult<Node> {
+        let text = &self.text(line)[col..];
+        let start = self.lines[line].start + col;
+        let (node, len) = match text.chars().next() {
+            Some('[') => self.parse_flow_sequence(text, start)?,
+            Some('{') => return Err(self.error(start, "flow mappings are not supported")),
+            Some('|' | '>') => return Err(self.error(start, "block scalars are not supported")),
+            _ => self.parse_scalar(text, start, false)?,
+        };
+        let rest = text[len..].trim_start_matches([' ', '\t']);
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Err(self.error(start + len, "unexpected trailing content"));
+        }
+        Ok(node)
+    }
+
+    fn parse_flow_sequence(&self, text: &str, start: usize) -> ParseResult<(Node, usize)> {
+        let mut items = Vec::new();
+        let mut pos = 1;
+        loop {
+            pos += text[pos..].len() - text[pos..].trim_start_matches([' ', '\t']).len();
+            match text[pos..].chars().next() {
+                Some(']') if items.is_empty() => break,
+                None => return Err(self.error(start + pos, "unterminated flow sequence")),
+                _ => {}
+            }
+            let (item, len) = self.parse_scalar(&text[pos..], start + pos, true)?;
+            items.push(item);
+            pos += len;
+            pos += text[pos..].len() - text[pos..].trim_start_matches([' ', '\t']).len();
+            match text[pos..].chars().next() {
+                Some(',') => pos += 1,
+                Some(']') => break,
+                _ => return Err(self.error(start + pos, "expected `,` or `]`")),
+            }
+        }
+        let len = pos + 1;
+        Ok((
+            Node {
+                span: start..start + len,
+                kind: NodeKind::FlowSequence(items),
+            },
+            len,
+        ))
+    }
+
+    /// Parse a scalar at the start of `text`, returning it and its length.
+    fn parse_scalar(&self, text: &str, start: usize, flow: bool) -> ParseResult<(Node, usize)> {
+        let (value, style, len) = match text.chars().next() {
+            Some('"') => {
+                let (value, len) = self.parse_quoted(text, '"', start)?;
+                (value, Style::DoubleQuoted, len)
+            }
+            Some('\'') => {
+                let (value, len) = self.parse_quoted(text, '\'', start)?;
+                (value, Style::SingleQuoted, len)
+            }
+            _ => {
+                let bytes = text.as_bytes();
+                let mut len = bytes.len();
+                for (i, &b) in bytes.iter().enumerate() {
+                    if (b == b'#' && i > 0 && matches!(bytes[i - 1], b' ' | b'\t'))
+                        || (flow && matches!(b, b',' | b']'))
+                    {
+                        len = i;
+                        break;
+                    }
+                }
+                let value = text[..len].trim_end_matches([' ', '\t']);
+                (value.to_string(), Style::Plain, value.len())
+            }
+        };
+        Ok((
+            Node {
+                span: start..start + len,
+                kind: NodeKind::Scalar { value, style },
+            },
+            len,
+        ))
+    }
+
+    /// Parse a quoted scalar, returning the unescaped value and its length
+    /// including the quotes.
+    fn parse_quoted(&self, text: &str, quote: char, start: usize) -> ParseResult<(String, usize)> {
+        let mut value = String::new();
+        let mut chars = text.char_indices().skip(1).peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\'' if quote == '\'' => {
+                    if let Some((_, '\'')) = chars.peek() {
+                        chars.next();
+                        value.push('\'');
+                    } else {
+                        return Ok((value, i + 1));
+                    }
+                }
+                '"' if quote == '"' => return Ok((value, i + 1)),
+                '\\' if quote == '"' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, '0')) => value.push('\0'),
+                    Some((_, c @ ('"' | '\\' | '/' | ' '))) => value.push(c),
+                    Some((j, escape @ ('x' | 'u' | 'U'))) => {
+                        let len = match escape {
+                            'x' => 2,
+                            'u' => 4,
+                            _ => 8,
+                        };
+                        let digits: String = chars.by_ref().take(len).map(|(_, c)| c).collect();
+                        let c = Some(&digits)
+                            .filter(|digits| {
+                                digits.len() == len && digits.chars().all(|c| c.is_ascii_hexdigit())
+                            })
+                            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error(start + j, "invalid escape"))?;
+                        value.push(c);
+                    }
+                    Some((j, _)) => return Err(self.error(start + j, "unsupported escape")),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        Err(self.error(start, "unterminated quoted scalar"))
+    }
+}
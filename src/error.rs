@@ -1,30 +1,217 @@
 use core::fmt;
+use std::cell::Cell;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::Utf8Error;
 
 /// The error type used in this crate.
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
+    message: String,
+    mark: Option<Mark>,
+    path: Option<PathBuf>,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
 
-#[derive(Debug)]
-enum ErrorKind {
-    Yaml(serde_yaml::Error),
+/// The kind of an [`Error`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input was not valid YAML.
+    Syntax,
+    /// An entry or attribute was specified more than once.
+    DuplicateEntry,
+    /// An attribute had a value that was not one of the allowed values.
+    UnknownVariant,
+    /// A required attribute, such as `Name`, was missing.
+    MissingField,
+    /// An attribute had a value of the wrong type, e.g. a sequence where a
+    /// scalar was expected.
+    InvalidType,
+    /// An attribute had a value of the right type, but it was invalid.
+    InvalidValue,
+    /// An edit of a [`Document`][crate::Document] was not possible.
+    InvalidEdit,
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// Any other error.
+    Other,
+}
+
+/// A position in the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Mark {
+    /// The byte offset, starting at 0.
+    pub index: usize,
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column, starting at 1.
+    pub column: usize,
+}
+
+impl Mark {
+    pub(crate) fn from_offset(source: &str, index: usize) -> Self {
+        let before = &source[..index];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Self {
+            index,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+thread_local! {
+    static RAISED: Cell<Option<ErrorKind>> = const { Cell::new(None) };
+}
+
+/// Record the kind of a deserialization error that is being raised, for
+/// [`Error::from_yaml`] to pick up.
+///
+/// The errors of `serde_yaml` only carry a message, so the kind is passed
+/// along on the side.
+pub(crate) fn with_kind<E>(kind: ErrorKind, err: E) -> E {
+    RAISED.set(Some(kind));
+    err
+}
+
+/// Forget the kind of any error that was raised earlier and then handled.
+pub(crate) fn reset_kind() {
+    RAISED.set(None);
+}
+
+impl ErrorKind {
+    /// The kind of the errors that serde raises itself, e.g. in derived
+    /// implementations, which are only known by their message.
+    fn from_serde_message(message: &str) -> Self {
+        if message.contains("duplicate field") {
+            Self::DuplicateEntry
+        } else if message.contains("unknown variant") {
+            Self::UnknownVariant
+        } else if message.contains("missing field") {
+            Self::MissingField
+        } else if message.contains("invalid type") {
+            Self::InvalidType
+        } else if message.contains("invalid value") || message.contains("invalid length") {
+            Self::InvalidValue
+        } else {
+            Self::Other
+        }
+    }
 }
 
 impl Error {
+    /// Convert an error from deserializing or serializing, using the kind
+    /// recorded by [`with_kind`] when it was raised, if any.
     pub(crate) fn from_yaml(err: serde_yaml::Error) -> Self {
+        let kind = RAISED
+            .take()
+            .unwrap_or_else(|| ErrorKind::from_serde_message(&err.to_string()));
+        Self::from_yaml_with_kind(err, kind)
+    }
+
+    pub(crate) fn syntax_from_yaml(err: serde_yaml::Error) -> Self {
+        RAISED.set(None);
+        Self::from_yaml_with_kind(err, ErrorKind::Syntax)
+    }
+
+    fn from_yaml_with_kind(err: serde_yaml::Error, kind: ErrorKind) -> Self {
+        let mark = err.location().map(|location| Mark {
+            index: location.index(),
+            line: location.line(),
+            column: location.column(),
+        });
+        Self {
+            kind,
+            message: err.to_string(),
+            mark,
+            path: None,
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub(crate) fn from_document(
+        source: &str,
+        offset: usize,
+        kind: ErrorKind,
+        message: &str,
+    ) -> Self {
+        let mark = Mark::from_offset(source, offset);
+        Self {
+            kind,
+            message: format!("{message} at line {} column {}", mark.line, mark.column),
+            mark: Some(mark),
+            path: None,
+            source: None,
+        }
+    }
+
+    /// A syntax error at the first byte of `source` that isn't valid UTF-8.
+    pub(crate) fn from_utf8(source: &[u8], err: Utf8Error) -> Self {
+        let valid = &source[..err.valid_up_to()];
+        let valid = std::str::from_utf8(valid).unwrap_or_default();
+        let mut error = Self::from_document(valid, valid.len(), ErrorKind::Syntax, "invalid UTF-8");
+        error.source = Some(Box::new(err));
+        error
+    }
+
+    pub(crate) fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
         Self {
-            kind: ErrorKind::Yaml(err),
+            kind,
+            message: message.into(),
+            mark: None,
+            path: None,
+            source: None,
         }
     }
+
+    pub(crate) fn from_io(err: io::Error) -> Self {
+        Self {
+            kind: ErrorKind::Io(err.kind()),
+            message: err.to_string(),
+            mark: None,
+            path: None,
+            source: Some(Box::new(err)),
+        }
+    }
+
+    pub(crate) fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// The kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The position in the input at which the error occurred, if known.
+    ///
+    /// For errors in the structure of a file, this points to the start of
+    /// the innermost entry that contains the error.
+    pub fn mark(&self) -> Option<Mark> {
+        self.mark
+    }
+
+    /// The path of the file in which the error occurred, if known.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.kind {
-            ErrorKind::Yaml(err) => write!(f, "{}", err),
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
         }
+        write!(f, "{}", self.message)
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_deref().map(|source| source as _)
+    }
+}
@@ -0,0 +1,230 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::{ApiNotes, Error};
+
+/// The order in which attributes are written, roughly following clang's
+/// documentation: first the attributes that identify an entry, then the
+/// general attributes, then the specific ones, and lastly nested entries.
+///
+/// Attributes not listed here are written last, in the order that they are
+/// serialized in.
+const KEY_ORDER: &[&str] = &[
+    "Name",
+    "Selector",
+    "MethodKind",
+    "PropertyKind",
+    "Position",
+    "Version",
+    "Availability",
+    "AvailabilityMsg",
+    "SwiftPrivate",
+    "SwiftName",
+    "SwiftBridge",
+    "NSErrorDomain",
+    "EnumKind",
+    "EnumExtensibility",
+    "FlagEnum",
+    "SwiftImportAs",
+    "SwiftRetainOp",
+    "SwiftReleaseOp",
+    "SwiftDestroyOp",
+    "SwiftConformsTo",
+    "SwiftImportAsNonGeneric",
+    "SwiftObjCMembers",
+    "SwiftCopyable",
+    "SwiftEscapable",
+    "SwiftWrapper",
+    "Nullability",
+    "NullabilityOfRet",
+    "Type",
+    "ResultType",
+    "DesignatedInit",
+    "RetainCountConvention",
+    "SwiftImportAsAccessors",
+    "NoEscape",
+    "Parameters",
+    "Methods",
+    "Properties",
+    "Fields",
+    "Classes",
+    "Protocols",
+    "Tags",
+    "Typedefs",
+    "Globals",
+    "Enumerators",
+    "Functions",
+    "Namespaces",
+    "SwiftVersions",
+];
+
+/// Attributes whose values are free-form text, and are therefore always
+/// quoted, like in clang's documentation.
+const QUOTED_KEYS: &[&str] = &[
+    "Selector",
+    "AvailabilityMsg",
+    "SwiftName",
+    "Type",
+    "ResultType",
+];
+
+/// Attributes whose values look like numbers, but are read as text, and
+/// therefore do not need to be quoted.
+const UNQUOTED_KEYS: &[&str] = &["Version"];
+
+const INDENT: usize = 2;
+
+impl ApiNotes {
+    /// Serialize the notes as YAML in a canonical layout.
+    ///
+    /// Unlike [`ApiNotes::to_string`], the output does not depend on the
+    /// version of `serde_yaml`:
+    /// - Attributes are written in a fixed order, see the source for details.
+    /// - Entries are sorted by their name, selector, position or version,
+    ///   with instance members before class members.
+    /// - Nested entries are indented by two spaces, and lists of values
+    ///   such as `Nullability` are written on a single line.
+    /// - Selectors, Swift names, messages and types are always written in
+    ///   double quotes, other values only when necessary.
+    ///
+    /// Note that comments are not preserved, use [`Document`] if that is
+    /// needed.
+    ///
+    /// [`Document`]: crate::Document
+    pub fn to_canonical_string(&self) -> Result<String, Error> {
+        let value = serde_yaml::to_value(self).map_err(Error::from_yaml)?;
+        let mut out = String::new();
+        match &value {
+            Value::Mapping(mapping) => write_mapping(&mut out, mapping, 0, false),
+            _ => unreachable!("API notes are serialized as a mapping"),
+        }
+        Ok(out)
+    }
+
+    /// Check whether the given source is already in the canonical layout
+    /// that [`ApiNotes::to_canonical_string`] produces.
+    pub fn is_canonical(source: &str) -> Result<bool, Error> {
+        let notes: ApiNotes = source.parse()?;
+        Ok(notes.to_canonical_string()? == source)
+    }
+}
+
+pub(crate) fn key_order(key: &Value) -> usize {
+    key.as_str()
+        .and_then(|key| KEY_ORDER.iter().position(|known| *known == key))
+        .unwrap_or(KEY_ORDER.len())
+}
+
+/// Write the entries of `mapping` at `indent`, with the first entry
+/// preceded by `- ` if the mapping is an item in a sequence.
+fn write_mapping(out: &mut String, mapping: &Mapping, indent: usize, item: bool) {
+    let mut entries: Vec<_> = mapping.iter().collect();
+    // Stable, so that unknown attributes keep their order.
+    entries.sort_by_key(|(key, _)| key_order(key));
+
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        if item && i == 0 {
+            out.push_str(&" ".repeat(indent - INDENT));
+            out.push_str("- ");
+        } else {
+            out.push_str(&" ".repeat(indent));
+        }
+        let key = key.as_str().expect("attribute names are strings");
+        out.push_str(key);
+        out.push(':');
+
+        match value {
+            Value::Sequence(items) if items.iter().all(Value::is_mapping) && !items.is_empty() => {
+                out.push('\n');
+                for item in items {
+                    let item = item.as_mapping().expect("checked above");
+                    write_mapping(out, item, indent + 2 * INDENT, true);
+                }
+            }
+            Value::Sequence(items) => {
+                out.push_str(" [");
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        out.push_str(", ");
+                    }
+                    write_scalar(out, item, false, true);
+                }
+                out.push_str("]\n");
+            }
+            Value::Mapping(mapping) if mapping.is_empty() => out.push_str(" {}\n"),
+            Value::Mapping(mapping) => {
+                out.push('\n');
+                write_mapping(out, mapping, indent + INDENT, false);
+            }
+            Value::String(s) if UNQUOTED_KEYS.contains(&key) => {
+                out.push(' ');
+                out.push_str(s);
+                out.push('\n');
+            }
+            value => {
+                out.push(' ');
+                write_scalar(out, value, QUOTED_KEYS.contains(&key), false);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+pub(crate) fn write_scalar(out: &mut String, value: &Value, quote: bool, in_flow: bool) {
+    match value {
+        Value::Null => out.push('~'),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) if !quote && is_plain_safe(s, in_flow) => out.push_str(s),
+        Value::String(s) => write_double_quoted(out, s),
+        Value::Tagged(tagged) => write_scalar(out, &tagged.value, quote, in_flow),
+        Value::Sequence(_) | Value::Mapping(_) => {
+            unreachable!("API notes do not contain nested collections in lists")
+        }
+    }
+}
+
+/// Whether `s` can be written as a plain scalar, and be read back as the
+/// same string.
+pub(crate) fn is_plain_safe(s: &str, in_flow: bool) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    if "-?:,[]{}#&*!|>'\"%@`".contains(first)
+        || first.is_whitespace()
+        || s.ends_with(char::is_whitespace)
+        || s.ends_with(':')
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.chars().any(char::is_control)
+        || (in_flow && s.contains([',', '[', ']', '{', '}']))
+    {
+        return false;
+    }
+    // Would be read back as something other than a string.
+    let lower = s.to_ascii_lowercase();
+    let is_keyword = matches!(
+        &*lower,
+        "~" | "null" | "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n"
+    );
+    let is_number = s.parse::<f64>().is_ok()
+        || matches!(&*lower, ".inf" | "-.inf" | "+.inf" | ".nan")
+        || lower.starts_with("0x")
+        || lower.starts_with("0o");
+    !is_keyword && !is_number
+}
+
+pub(crate) fn write_double_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -1,22 +1,150 @@
-use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
 
-pub type Availability = String;
+use serde::{de, Deserialize, Serialize};
+
+use crate::error::with_kind;
+use crate::{Error, ErrorKind, SwiftName};
+
+/// The availability of a declaration.
+///
+/// Parsed case-insensitively, e.g. `iOS`, `IOS` and `ios` are all accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Availability {
+    /// Available everywhere (the default).
+    Available,
+    /// Only available on macOS.
+    OSX,
+    /// Only available on iOS.
+    IOS,
+    /// Unavailable everywhere.
+    None,
+    /// Unavailable in Swift, but available in C and Objective-C.
+    NonSwift,
+}
+
+impl Availability {
+    const VARIANTS: &'static [&'static str] = &["available", "OSX", "iOS", "none", "nonswift"];
+
+    /// The spelling that clang uses.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Available => "available",
+            Self::OSX => "OSX",
+            Self::IOS => "iOS",
+            Self::None => "none",
+            Self::NonSwift => "nonswift",
+        }
+    }
+
+    /// Whether the declaration is unavailable when imported into Swift.
+    pub fn is_unavailable_in_swift(self) -> bool {
+        matches!(self, Self::None | Self::NonSwift)
+    }
+}
+
+impl FromStr for Availability {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Available,
+            Self::OSX,
+            Self::IOS,
+            Self::None,
+            Self::NonSwift,
+        ]
+        .into_iter()
+        .find(|availability| availability.as_str().eq_ignore_ascii_case(s))
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::UnknownVariant,
+                format!(
+                    "unknown availability `{s}`, expected one of {}",
+                    Self::VARIANTS.join(", ")
+                ),
+            )
+        })
+    }
+}
+
+impl fmt::Display for Availability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Availability {
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Vis;
+
+        impl de::Visitor<'_> for Vis {
+            type Value = Availability;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an availability")
+            }
+
+            // Parsed in the visitor, so that the error points to the value.
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(|_| {
+                    with_kind(
+                        ErrorKind::UnknownVariant,
+                        E::unknown_variant(v, Availability::VARIANTS),
+                    )
+                })
+            }
+        }
+
+        des.deserialize_str(Vis)
+    }
+}
+
+impl Serialize for Availability {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(self.as_str())
+    }
+}
 
 /// General attributes.
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct General {
+    /// The name as written, see [`parsed_swift_name`][Self::parsed_swift_name].
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub swift_name: Option<String>,
-    // TODO: Handle availability better
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub availability: Option<Availability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub availability_msg: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub swift_private: bool,
 }
 
+impl General {
+    /// Whether the declaration is unavailable when imported into Swift.
+    pub fn is_unavailable_in_swift(&self) -> bool {
+        self.availability
+            .is_some_and(Availability::is_unavailable_in_swift)
+    }
+
+    /// The `SwiftName`, parsed.
+    ///
+    /// The name is kept as written, since clang reads notes with names that
+    /// it can't parse, and only warns about them when they are used.
+    pub fn parsed_swift_name(&self) -> Option<Result<SwiftName, Error>> {
+        self.swift_name.as_deref().map(str::parse)
+    }
+}
+
 /// Note that this is overridden by `Type`, even in a `SwiftVersions` section.
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Nullability {
     #[serde(alias = "N")]
     Nonnull,
@@ -28,4 +156,26 @@ pub enum Nullability {
     Scalar,
 }
 
+/// Ownership conventions for values returned from functions and methods.
+///
+/// On parameters, this applies to the value returned through an
+/// out-parameter, e.g. `CFTypeRef *`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum RetainCountConvention {
+    /// Not a retainable object, or not returned with any of the conventions.
+    #[serde(rename = "none")]
+    None,
+    /// A Core Foundation object that is returned with a +1 retain count.
+    CFReturnsRetained,
+    /// A Core Foundation object that is returned with a +0 retain count.
+    CFReturnsNotRetained,
+    /// An Objective-C object that is returned with a +1 retain count.
+    NSReturnsRetained,
+    /// An Objective-C object that is returned with a +0 retain count.
+    NSReturnsNotRetained,
+}
+
+/// A C or Objective-C type, e.g. `NSString * _Nullable`.
+///
+/// Use [`CType`][crate::CType] to parse it.
 pub type Type = String;
@@ -15,21 +15,42 @@
 // Update in Cargo.toml as well.
 #![doc(html_root_url = "https://docs.rs/apinotes/0.0.2")]
 
+mod c_type;
 #[cfg(test)]
 mod clang_tests;
+mod diff;
+mod document;
 mod error;
+mod format;
 mod general;
 mod map_helper;
+mod merge;
 mod method_and_property;
 mod mid_level;
+mod path;
+mod query;
+mod resolve;
+mod select;
+mod selector;
+mod swift_name;
 mod top_level;
+mod validate;
 
-pub use self::error::Error;
+pub use self::c_type::{CType, CTypeKind, FunctionType, Ownership, Qualifiers, TypeNullability};
+pub use self::diff::{Change, ChangeKind};
+pub use self::document::{Document, Location};
+pub use self::error::{Error, ErrorKind, Mark};
 pub use self::general::*;
 pub use self::map_helper::Map;
+pub use self::merge::{Conflict, ConflictPolicy, MergeReport, Source};
 pub use self::method_and_property::*;
 pub use self::mid_level::*;
+pub use self::query::Query;
+pub use self::select::Selection;
+pub use self::selector::Selector;
+pub use self::swift_name::{Accessor, SwiftName, SwiftNameKind};
 pub use self::top_level::*;
+pub use self::validate::{Diagnostic, DiagnosticKind};
 
 /// The file extension (without the leading dot) that API notes use.
 ///
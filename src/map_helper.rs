@@ -1,33 +1,45 @@
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::Hash;
 use std::marker::PhantomData;
+use std::str::FromStr;
 
-use serde::de::{self, Error};
-use serde::Deserialize;
+use serde::de::value::StringDeserializer;
+use serde::de::{self, DeserializeSeed, Error, IntoDeserializer};
+use serde::{ser, Deserialize, Serialize};
+
+use crate::error::with_kind;
+use crate::ErrorKind;
 
 /// The map type used by this library.
 pub type Map<V, K> = HashMap<V, K>;
 
-pub(crate) trait MapKey<'de>: Hash + Eq {
-    type Inner: Deserialize<'de>;
+/// The key of the entries in a [`Map`].
+///
+/// Entries are serialized sorted by their key.
+pub(crate) trait MapKey<'de>: Hash + Ord {
+    type Inner: Deserialize<'de> + Serialize;
     fn from_inner(inner: Self::Inner) -> Self;
+    fn to_inner(&self) -> Self::Inner;
+    /// The attributes in each element that make up the key.
+    const FIELDS: &'static [&'static str];
+    /// Whether the attributes must be read as written, see [`Scalar`].
+    const TEXT: bool = false;
     const CONTAIN_ERROR: &'static str;
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct Name {
     name: String,
 }
 
-#[derive(Deserialize)]
-struct MapElement<K, V> {
+#[derive(Serialize)]
+struct MapElementRef<'a, K, V> {
     #[serde(flatten)]
     key: K,
     #[serde(flatten)]
-    value: V,
+    value: &'a V,
 }
 
 impl<'de> MapKey<'de> for String {
@@ -35,9 +47,455 @@ impl<'de> MapKey<'de> for String {
     fn from_inner(inner: Self::Inner) -> Self {
         inner.name
     }
+    fn to_inner(&self) -> Self::Inner {
+        Name { name: self.clone() }
+    }
+    const FIELDS: &'static [&'static str] = &["Name"];
     const CONTAIN_ERROR: &'static str = "a `Name` attribute";
 }
 
+/// Splits off some of the entries of a map while the rest of it is being
+/// deserialized.
+///
+/// This is used instead of `#[serde(flatten)]`, since that buffers the
+/// contents, and thereby loses the location information that we want in
+/// error messages.
+pub(crate) trait Capture<'de> {
+    /// Deserialize the value of `key` from `map` if it belongs to `self`.
+    ///
+    /// Returns whether the value was consumed.
+    fn capture<A>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error>
+    where
+        A: de::MapAccess<'de>;
+}
+
+/// A deserializer for the entries of a map that were not captured.
+pub(crate) struct Split<'c, A, C> {
+    map: A,
+    capture: &'c mut C,
+}
+
+impl<'c, A, C> Split<'c, A, C> {
+    pub(crate) fn new(map: A, capture: &'c mut C) -> Self {
+        Self { map, capture }
+    }
+}
+
+impl<'de, A, C> de::Deserializer<'de> for Split<'_, A, C>
+where
+    A: de::MapAccess<'de>,
+    C: Capture<'de>,
+{
+    type Error = A::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_map(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, A, C> de::MapAccess<'de> for Split<'_, A, C>
+where
+    A: de::MapAccess<'de>,
+    C: Capture<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        while let Some(key) = self.map.next_key::<String>()? {
+            if !self.capture.capture(&key, &mut self.map)? {
+                let key: StringDeserializer<A::Error> = key.into_deserializer();
+                return seed.deserialize(key).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// Captures the attributes that make up the key of an element.
+struct KeyFields {
+    fields: &'static [&'static str],
+    text: bool,
+    values: serde_yaml::Mapping,
+}
+
+impl<'de> Capture<'de> for KeyFields {
+    fn capture<A>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        if !self.fields.contains(&key) {
+            return Ok(false);
+        }
+        let value = map.next_value_seed(Scalar { text: self.text })?;
+        if self.values.insert(key.into(), value).is_some() {
+            return Err(with_kind(
+                ErrorKind::DuplicateEntry,
+                A::Error::custom(format_args!("duplicate field `{key}`")),
+            ));
+        }
+        Ok(true)
+    }
+}
+
+/// Captures the attributes of a struct `T` that shares a map with other
+/// attributes, like with `#[serde(flatten)]`.
+///
+/// Each attribute is deserialized where it's written, so that errors keep
+/// their location.
+pub(crate) struct Flattened<T> {
+    fields: &'static [&'static str],
+    values: serde_yaml::Mapping,
+    seen: Vec<String>,
+    marker: PhantomData<T>,
+}
+
+impl<T> Flattened<T>
+where
+    T: de::DeserializeOwned + Serialize,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            fields: struct_fields::<T>(),
+            values: serde_yaml::Mapping::new(),
+            seen: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// The struct with the captured attributes.
+    pub(crate) fn finish<E: de::Error>(self) -> Result<T, E> {
+        T::deserialize(serde_yaml::Value::Mapping(self.values)).map_err(E::custom)
+    }
+}
+
+impl<'de, T> Capture<'de> for Flattened<T>
+where
+    T: de::DeserializeOwned + Serialize,
+{
+    fn capture<A>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        if !self.fields.contains(&key) {
+            return Ok(false);
+        }
+        if self.seen.iter().any(|seen| seen == key) {
+            return Err(with_kind(
+                ErrorKind::DuplicateEntry,
+                A::Error::custom(format_args!("duplicate field `{key}`")),
+            ));
+        }
+        self.seen.push(key.into());
+        // Deserialize a `T` with only this attribute, and keep its serialized
+        // form to combine with the others.
+        let value = T::deserialize(Entry {
+            key: Some(key.into()),
+            map,
+        })?;
+        match serde_yaml::to_value(value).map_err(A::Error::custom)? {
+            serde_yaml::Value::Mapping(values) => self.values.extend(values),
+            _ => return Err(A::Error::custom("expected a map")),
+        }
+        Ok(true)
+    }
+}
+
+/// Implement `Deserialize` for structs with a flattened `general` field, and
+/// capture the [`General`][crate::General] attributes with [`Flattened`]
+/// instead of buffering them, so that errors in them keep their location.
+///
+/// The other attributes are read with the derived implementations, which must
+/// be generated with `#[serde(remote = "Self")]`. `Serialize` is forwarded to
+/// them as is.
+macro_rules! impl_serde_with_general {
+    ($($ty:ident)*) => {$(
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(des: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct Vis;
+
+                impl<'de> serde::de::Visitor<'de> for Vis {
+                    type Value = $ty;
+
+                    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        f.write_str("a map")
+                    }
+
+                    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+                    where
+                        A: serde::de::MapAccess<'de>,
+                    {
+                        let mut general = $crate::map_helper::Flattened::new();
+                        let split = $crate::map_helper::Split::new(map, &mut general);
+                        let mut value = $ty::deserialize(split)?;
+                        value.general = general.finish()?;
+                        Ok(value)
+                    }
+                }
+
+                des.deserialize_map(Vis)
+            }
+        }
+
+        impl serde::Serialize for $ty {
+            fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                $ty::serialize(self, ser)
+            }
+        }
+    )*};
+}
+
+pub(crate) use impl_serde_with_general;
+
+/// A map with a single entry, whose value is read from `map`.
+struct Entry<'a, A> {
+    key: Option<String>,
+    map: &'a mut A,
+}
+
+impl<'de, A> de::Deserializer<'de> for Entry<'_, A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_map(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, A> de::MapAccess<'de> for Entry<'_, A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.key
+            .take()
+            .map(|key| seed.deserialize(StringDeserializer::<A::Error>::new(key)))
+            .transpose()
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// The names of the attributes of a struct with derived `Deserialize`.
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct Fields<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> de::Deserializer<'de> for Fields<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V>(self, _: V) -> Result<V::Value, Self::Error>
+        where
+            V: de::Visitor<'de>,
+        {
+            Err(Self::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: de::Visitor<'de>,
+        {
+            *self.0 = fields;
+            Err(Self::Error::custom("only reading the fields"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(Fields(&mut fields));
+    fields
+}
+
+/// A scalar value, as text.
+///
+/// Numbers are converted to text, but with `text`, the scalar is requested as
+/// a string, so that formats that keep the original text, like YAML, return
+/// e.g. `5.10` as written instead of as the number 5.1.
+struct Scalar {
+    text: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for Scalar {
+    type Value = serde_yaml::Value;
+
+    fn deserialize<D>(self, des: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        if self.text {
+            des.deserialize_str(ScalarVisitor)
+        } else {
+            des.deserialize_any(ScalarVisitor)
+        }
+    }
+}
+
+struct ScalarVisitor;
+
+impl de::Visitor<'_> for ScalarVisitor {
+    type Value = serde_yaml::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a scalar")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        self.visit_str(&v.to_string())
+    }
+}
+
+/// Deserialize a value that was captured as a string using `FromStr`.
+pub(crate) fn from_str<'de, D, T>(des: D) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    parse_str(des, str::parse)
+}
+
+/// Deserialize a string and convert it with `parse`, which returns the
+/// reason if the string is invalid.
+pub(crate) fn parse_str<'de, D, T, R>(
+    des: D,
+    parse: impl FnOnce(&str) -> Result<T, R>,
+) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    R: fmt::Display,
+{
+    let s = String::deserialize(des)?;
+    parse(&s).map_err(|reason| {
+        with_kind(
+            ErrorKind::InvalidValue,
+            D::Error::custom(format_args!("invalid value `{s}`: {reason}")),
+        )
+    })
+}
+
+struct Element<'a, K, V> {
+    map: &'a Map<K, V>,
+}
+
+impl<'de, K, V> DeserializeSeed<'de> for Element<'_, K, V>
+where
+    K: MapKey<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = (K, V);
+
+    fn deserialize<D>(self, des: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        des.deserialize_map(self)
+    }
+}
+
+impl<'de, K, V> de::Visitor<'de> for Element<'_, K, V>
+where
+    K: MapKey<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = (K, V);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a map containing {}", K::CONTAIN_ERROR)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut fields = KeyFields {
+            fields: K::FIELDS,
+            text: K::TEXT,
+            values: serde_yaml::Mapping::new(),
+        };
+        let value = V::deserialize(Split::new(map, &mut fields))?;
+        let inner = K::Inner::deserialize(serde_yaml::Value::Mapping(fields.values))
+            .map_err(A::Error::custom)?;
+        let key = K::from_inner(inner);
+        if self.map.contains_key(&key) {
+            return Err(with_kind(
+                ErrorKind::DuplicateEntry,
+                A::Error::custom("duplicate entry"),
+            ));
+        }
+        Ok((key, value))
+    }
+}
+
 #[derive(Debug)]
 struct Vis<K, V> {
     p: PhantomData<fn() -> Map<K, V>>,
@@ -71,15 +529,8 @@ where
     {
         let mut map = Map::with_capacity(seq.size_hint().unwrap_or(0));
 
-        while let Some(MapElement { key, value }) = seq.next_element::<MapElement<K::Inner, V>>()? {
-            match map.entry(K::from_inner(key)) {
-                Entry::Occupied(_entry) => {
-                    return Err(A::Error::custom("unexpected duplicate entry"));
-                }
-                Entry::Vacant(entry) => {
-                    entry.insert(value);
-                }
-            }
+        while let Some((key, value)) = seq.next_element_seed(Element { map: &map })? {
+            map.insert(key, value);
         }
 
         Ok(map)
@@ -95,9 +546,16 @@ where
     des.deserialize_any(Vis { p: PhantomData })
 }
 
-// pub(crate) fn serialize<K, V, S>(map: &Map<K, V>, serializer: S) -> Result<S::Ok, S::Error>
-// where
-//     S: ser::Serializer,
-// {
-//     todo!()
-// }
+pub(crate) fn serialize<'de, S, K, V>(map: &Map<K, V>, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+    K: MapKey<'de>,
+    V: Serialize,
+{
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable_by_key(|(key, _)| *key);
+    ser.collect_seq(entries.into_iter().map(|(key, value)| MapElementRef {
+        key: key.to_inner(),
+        value,
+    }))
+}
@@ -0,0 +1,396 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::diff::{display, join};
+use crate::map_helper::MapKey;
+use crate::{
+    path, ApiNotes, Class, Data, Document, Enumerator, Error, ErrorKind, Field, Function, General,
+    Global, Map, Mark, Method, Namespace, Parameter, Property, Protocol, Tag, Typedef,
+};
+
+/// How to resolve conflicts when merging API notes, see
+/// [`ApiNotes::merge`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ConflictPolicy {
+    /// Fail if there are any conflicts.
+    #[default]
+    Error,
+    /// Keep the value from the notes that are merged into.
+    PreferLeft,
+    /// Use the value from the notes that are being merged in.
+    PreferRight,
+}
+
+/// An attribute that has different values in two merged [`ApiNotes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Conflict {
+    /// The conflicting attribute, e.g. `Classes[A].SwiftName`.
+    pub path: String,
+    /// The value in the notes that are merged into.
+    pub left: String,
+    /// The value in the notes that are being merged in.
+    pub right: String,
+    /// Where the left value is written, when merging files with
+    /// [`ApiNotes::merge_files`].
+    pub left_source: Option<Source>,
+    /// Where the right value is written, when merging files with
+    /// [`ApiNotes::merge_files`].
+    pub right_source: Option<Source>,
+}
+
+/// A position in an API notes file, see [`Conflict`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Source {
+    /// The path of the file.
+    pub path: PathBuf,
+    /// The position in the file.
+    pub mark: Mark,
+}
+
+/// The outcome of merging API notes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MergeReport {
+    /// The attributes that had a different value in each of the notes.
+    pub conflicts: Vec<Conflict>,
+    /// Whether the conflicts were left unresolved because of
+    /// [`ConflictPolicy::Error`], in which case the notes were not merged.
+    pub unresolved: bool,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: conflicting values `{}`", self.path, self.left)?;
+        if let Some(source) = &self.left_source {
+            write!(f, " ({source})")?;
+        }
+        write!(f, " and `{}`", self.right)?;
+        if let Some(source) = &self.right_source {
+            write!(f, " ({source})")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.path.display(),
+            self.mark.line,
+            self.mark.column
+        )
+    }
+}
+
+impl ApiNotes {
+    /// Merge the entries of `other` into `self`, like clang does when
+    /// loading e.g. both `Foo.apinotes` and `Foo_private.apinotes`.
+    ///
+    /// Entries that only exist in one of the notes are kept as-is, while
+    /// entries that exist in both are merged attribute by attribute. If an
+    /// attribute has a different value in each, `policy` decides which one
+    /// to keep.
+    ///
+    /// With [`ConflictPolicy::Error`], `self` is left unchanged if there are
+    /// any conflicts, and [`MergeReport::unresolved`] is set.
+    pub fn merge(&mut self, other: &ApiNotes, policy: ConflictPolicy) -> MergeReport {
+        merge(self, other, policy)
+    }
+
+    /// Read the API notes files at `paths` and merge them in order, see
+    /// [`merge`][Self::merge].
+    ///
+    /// Unlike with `merge`, each [`Conflict`] records where its values are
+    /// written. Returns an error if a file can't be read or parsed, or if
+    /// `paths` is empty.
+    pub fn merge_files<P: AsRef<Path>>(
+        paths: &[P],
+        policy: ConflictPolicy,
+    ) -> Result<(ApiNotes, MergeReport), Error> {
+        let mut merged: Option<ApiNotes> = None;
+        let mut report = MergeReport::default();
+        let mut documents = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            let contents =
+                std::fs::read(path).map_err(|err| Error::from_io(err).with_path(path))?;
+            let notes = ApiNotes::from_slice(&contents).map_err(|err| err.with_path(path))?;
+            // Only used for locating conflicts, so it's fine if this fails.
+            let document = std::str::from_utf8(&contents)
+                .ok()
+                .and_then(|source| Document::parse(source).ok());
+            documents.push((path, document));
+
+            let Some(merged) = &mut merged else {
+                merged = Some(notes);
+                continue;
+            };
+            let mut step = merged.merge(&notes, policy);
+            let (current, previous) = documents.split_last().expect("just pushed");
+            for conflict in &mut step.conflicts {
+                let locate = |(path, document): &(&Path, Option<Document>)| {
+                    Some(Source {
+                        path: path.into(),
+                        mark: document.as_ref()?.locate(&conflict.path)?,
+                    })
+                };
+                // The left value comes from the first of the previous files
+                // that has it, unless later files took precedence.
+                conflict.left_source = match policy {
+                    ConflictPolicy::PreferRight => previous.iter().rev().find_map(locate),
+                    _ => previous.iter().find_map(locate),
+                };
+                conflict.right_source = locate(current);
+            }
+            report.conflicts.append(&mut step.conflicts);
+            report.unresolved |= step.unresolved;
+        }
+        let merged =
+            merged.ok_or_else(|| Error::new(ErrorKind::Other, "no API notes files to merge"))?;
+        Ok((merged, report))
+    }
+}
+
+impl Data {
+    /// Merge the entries of `other` into `self`.
+    ///
+    /// See [`ApiNotes::merge`] for details.
+    pub fn merge(&mut self, other: &Data, policy: ConflictPolicy) -> MergeReport {
+        merge(self, other, policy)
+    }
+}
+
+fn merge<T: Merge + Clone>(left: &mut T, right: &T, policy: ConflictPolicy) -> MergeReport {
+    let mut merger = Merger {
+        policy,
+        conflicts: Vec::new(),
+    };
+    let mut merged = left.clone();
+    Merge::merge(&mut merged, right, "", &mut merger);
+
+    let unresolved = policy == ConflictPolicy::Error && !merger.conflicts.is_empty();
+    if !unresolved {
+        *left = merged;
+    }
+    MergeReport {
+        conflicts: merger.conflicts,
+        unresolved,
+    }
+}
+
+struct Merger {
+    policy: ConflictPolicy,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+    fn scalar<T: Clone + PartialEq + Serialize>(&mut self, left: &mut T, right: &T, path: &str) {
+        if left == right {
+            return;
+        }
+        let show = |value: &T| {
+            serde_yaml::to_value(value)
+                .map(|value| display(&value))
+                .unwrap_or_default()
+        };
+        self.conflicts.push(Conflict {
+            path: path.into(),
+            left: show(left),
+            right: show(right),
+            left_source: None,
+            right_source: None,
+        });
+        if self.policy == ConflictPolicy::PreferRight {
+            *left = right.clone();
+        }
+    }
+}
+
+/// Merging attribute by attribute.
+trait Merge {
+    /// Merge `other` into `self`, where `path` is the path of `self`.
+    fn merge(&mut self, other: &Self, path: &str, merger: &mut Merger);
+}
+
+impl Merge for String {
+    fn merge(&mut self, other: &Self, path: &str, merger: &mut Merger) {
+        merger.scalar(self, other, path);
+    }
+}
+
+// A `false` flag is the same as a missing one.
+impl Merge for bool {
+    fn merge(&mut self, other: &Self, _path: &str, _merger: &mut Merger) {
+        *self |= *other;
+    }
+}
+
+impl<T: Clone + PartialEq + Serialize> Merge for Option<T> {
+    fn merge(&mut self, other: &Self, path: &str, merger: &mut Merger) {
+        match (self.as_mut(), other) {
+            (Some(left), Some(right)) => merger.scalar(left, right, path),
+            (None, Some(right)) => *self = Some(right.clone()),
+            (_, None) => {}
+        }
+    }
+}
+
+impl<K, V> Merge for Map<K, V>
+where
+    K: for<'de> MapKey<'de> + Clone,
+    V: Merge + Clone,
+{
+    fn merge(&mut self, other: &Self, path: &str, merger: &mut Merger) {
+        let mut entries: Vec<_> = other.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        for (key, value) in entries {
+            match self.get_mut(key) {
+                Some(existing) => {
+                    let path = format!("{path}[{}]", label(key));
+                    existing.merge(value, &path, merger);
+                }
+                None => {
+                    self.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// The label of an entry in a path, see [`path::label`].
+fn label<K: for<'de> MapKey<'de>>(key: &K) -> String {
+    let inner = serde_yaml::to_value(key.to_inner()).unwrap_or_default();
+    path::label(|field| inner.get(field).map(display)).unwrap_or_default()
+}
+
+/// Implement [`Merge`] for a struct by merging each field under the
+/// attribute that it's serialized as, or under the same path if the field is
+/// flattened, written as `""`.
+///
+/// All fields must be listed.
+macro_rules! impl_merge {
+    ($($ty:ident { $($field:ident: $key:literal),* $(,)? })*) => {$(
+        impl Merge for $ty {
+            fn merge(&mut self, other: &Self, path: &str, merger: &mut Merger) {
+                let $ty { $($field),* } = self;
+                $(
+                    let field_path = if $key.is_empty() { path.into() } else { join(path, $key) };
+                    Merge::merge($field, &other.$field, &field_path, merger);
+                )*
+            }
+        }
+    )*};
+}
+
+impl_merge! {
+    ApiNotes {
+        name: "Name",
+        data: "",
+        swift_versions: "SwiftVersions",
+    }
+    Data {
+        classes: "Classes",
+        protocols: "Protocols",
+        tags: "Tags",
+        typedefs: "Typedefs",
+        globals: "Globals",
+        enumerators: "Enumerators",
+        functions: "Functions",
+        namespaces: "Namespaces",
+    }
+    Namespace {
+        general: "",
+        data: "",
+    }
+    General {
+        swift_name: "SwiftName",
+        availability: "Availability",
+        availability_msg: "AvailabilityMsg",
+        swift_private: "SwiftPrivate",
+    }
+    Class {
+        general: "",
+        methods: "Methods",
+        properties: "Properties",
+        swift_bridge: "SwiftBridge",
+        swift_import_as_non_generic: "SwiftImportAsNonGeneric",
+        swift_objc_members: "SwiftObjCMembers",
+        swift_conforms_to: "SwiftConformsTo",
+    }
+    Protocol {
+        general: "",
+        methods: "Methods",
+        properties: "Properties",
+        swift_import_as_non_generic: "SwiftImportAsNonGeneric",
+        swift_objc_members: "SwiftObjCMembers",
+        swift_conforms_to: "SwiftConformsTo",
+    }
+    Method {
+        general: "",
+        nullability_of_ret: "NullabilityOfRet",
+        nullability: "Nullability",
+        result_type: "ResultType",
+        designated_init: "DesignatedInit",
+        retain_count_convention: "RetainCountConvention",
+        parameters: "Parameters",
+    }
+    Property {
+        general: "",
+        nullability: "Nullability",
+        swift_import_as_accessors: "SwiftImportAsAccessors",
+        type_: "Type",
+    }
+    Parameter {
+        general: "",
+        nullability: "Nullability",
+        type_: "Type",
+        no_escape: "NoEscape",
+        retain_count_convention: "RetainCountConvention",
+    }
+    Tag {
+        general: "",
+        error_domain: "NSErrorDomain",
+        enum_kind: "EnumKind",
+        enum_extensibility: "EnumExtensibility",
+        flag_enum: "FlagEnum",
+        swift_import_as: "SwiftImportAs",
+        swift_retain_op: "SwiftRetainOp",
+        swift_release_op: "SwiftReleaseOp",
+        swift_destroy_op: "SwiftDestroyOp",
+        swift_conforms_to: "SwiftConformsTo",
+        swift_copyable: "SwiftCopyable",
+        swift_escapable: "SwiftEscapable",
+        methods: "Methods",
+        fields: "Fields",
+    }
+    Field {
+        general: "",
+        nullability: "Nullability",
+        type_: "Type",
+    }
+    Typedef {
+        general: "",
+        swift_wrapper: "SwiftWrapper",
+    }
+    Global {
+        general: "",
+        nullability: "Nullability",
+        type_: "Type",
+    }
+    Enumerator {
+        general: "",
+    }
+    Function {
+        general: "",
+        nullability_of_ret: "NullabilityOfRet",
+        result_type: "ResultType",
+        parameters: "Parameters",
+        retain_count_convention: "RetainCountConvention",
+    }
+}
@@ -1,18 +1,20 @@
-use serde::Deserialize;
+use std::collections::hash_map::Entry;
 
-use crate::map_helper::MapKey;
-use crate::{General, Map, Nullability, Type};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+use crate::map_helper::{impl_serde_with_general, MapKey};
+use crate::{DiagnosticKind, General, Map, Nullability, RetainCountConvention, Selector, Type};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub enum Kind {
     Instance,
     Class,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct MethodKey {
-    pub selector: String,
+    pub selector: Selector,
     #[serde(rename = "MethodKind")]
     pub kind: Kind,
 }
@@ -22,17 +24,22 @@ impl<'de> MapKey<'de> for MethodKey {
     fn from_inner(inner: Self::Inner) -> Self {
         inner
     }
+    fn to_inner(&self) -> Self::Inner {
+        self.clone()
+    }
+    const FIELDS: &'static [&'static str] = &["Selector", "MethodKind"];
     const CONTAIN_ERROR: &'static str = "a `Selector` and a `MethodKind` attribute";
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct PropertyKey {
     pub name: String,
     /// `None` means that the property signifies both a class and an instance
-    /// property.
+    /// property, see e.g. [`Class::property`][crate::Class::property].
     #[serde(rename = "PropertyKind")]
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<Kind>,
 }
 
@@ -41,45 +48,177 @@ impl<'de> MapKey<'de> for PropertyKey {
     fn from_inner(inner: Self::Inner) -> Self {
         inner
     }
+    fn to_inner(&self) -> Self::Inner {
+        self.clone()
+    }
+    const FIELDS: &'static [&'static str] = &["Name", "PropertyKind"];
     const CONTAIN_ERROR: &'static str = "a `Name` and a `PropertyKind` attribute";
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Method {
     #[serde(flatten)]
     pub general: General,
     /// Note: May not work due to compiler bugs
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nullability_of_ret: Option<Nullability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nullability: Option<Vec<Nullability>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub result_type: Option<Type>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub designated_init: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_count_convention: Option<RetainCountConvention>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub parameters: Map<Position, Parameter>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+/// The nullability of the result and each argument of a method, see
+/// [`Method::resolved_nullability`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MethodNullability {
+    /// The nullability of the result.
+    pub result: Option<Nullability>,
+    /// The nullability of each argument of the selector, by position.
+    pub parameters: Vec<Option<Nullability>>,
+    /// Where the legacy `Nullability` list disagrees with `Parameters`, or
+    /// doesn't match the selector.
+    pub problems: Vec<DiagnosticKind>,
+}
+
+impl Method {
+    /// Combine `NullabilityOfRet`, the legacy `Nullability` list and the
+    /// `Nullability` of each entry in `Parameters` into a single view, the
+    /// way clang does.
+    ///
+    /// The legacy list gives the nullability of the arguments in order, and
+    /// is ignored if it's empty. Like in clang, if either a non-empty list
+    /// or `NullabilityOfRet` is given, the result and any arguments not in
+    /// the list are `Nonnull` by default.
+    /// `Parameters` take precedence over the list, which is reported in
+    /// [`MethodNullability::problems`] if they disagree.
+    pub fn resolved_nullability(&self, selector: &Selector) -> MethodNullability {
+        let num_args = selector.num_args();
+        let mut problems = Vec::new();
+        let list = self.nullability.as_deref().unwrap_or_default();
+        if !list.is_empty() && list.len() != num_args {
+            problems.push(DiagnosticKind::NullabilityArity {
+                expected: num_args,
+                found: list.len(),
+            });
+        }
+
+        let audited = !list.is_empty() || self.nullability_of_ret.is_some();
+        let default = audited.then_some(Nullability::Nonnull);
+        let result = self.nullability_of_ret.clone().or_else(|| default.clone());
+        let parameters = (0..num_args)
+            .map(|index| {
+                let legacy = list.get(index);
+                let parameter = Position::try_from(index).ok().and_then(|position| {
+                    let nullability = self.parameters.get(&position)?.nullability.as_ref()?;
+                    Some((position, nullability))
+                });
+                match (legacy, parameter) {
+                    (Some(legacy), Some((position, parameter))) => {
+                        if legacy != parameter {
+                            problems.push(DiagnosticKind::NullabilityConflict {
+                                position,
+                                legacy: legacy.clone(),
+                                parameter: parameter.clone(),
+                            });
+                        }
+                        Some(parameter.clone())
+                    }
+                    (_, Some((_, parameter))) => Some(parameter.clone()),
+                    (Some(legacy), None) => Some(legacy.clone()),
+                    (None, None) => default.clone(),
+                }
+            })
+            .collect();
+
+        MethodNullability {
+            result,
+            parameters,
+            problems,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Property {
     #[serde(flatten)]
     pub general: General,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nullability: Option<Nullability>,
     // Default = false
     #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub swift_import_as_accessors: bool,
     #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub type_: Option<Type>,
 }
 
+/// Look up a property, falling back to an entry without a `PropertyKind`.
+pub(crate) fn find_property<'a>(
+    properties: &'a Map<PropertyKey, Property>,
+    name: &str,
+    kind: Kind,
+) -> Option<&'a Property> {
+    let key = |kind| PropertyKey {
+        name: name.into(),
+        kind,
+    };
+    properties
+        .get(&key(Some(kind)))
+        .or_else(|| properties.get(&key(None)))
+}
+
+/// Replace the entries without a `PropertyKind` with an instance and a class
+/// entry, returning the keys of existing entries that were kept instead.
+pub(crate) fn expand_properties(properties: &mut Map<PropertyKey, Property>) -> Vec<PropertyKey> {
+    let kindless: Vec<_> = properties
+        .keys()
+        .filter(|key| key.kind.is_none())
+        .cloned()
+        .collect();
+    let mut conflicts = Vec::new();
+    for key in kindless {
+        let property = properties.remove(&key).expect("key was just found");
+        for kind in [Kind::Instance, Kind::Class] {
+            let expanded = PropertyKey {
+                name: key.name.clone(),
+                kind: Some(kind),
+            };
+            match properties.entry(expanded) {
+                Entry::Occupied(entry) => conflicts.push(entry.key().clone()),
+                Entry::Vacant(entry) => {
+                    entry.insert(property.clone());
+                }
+            }
+        }
+    }
+    conflicts.sort();
+    conflicts
+}
+
 pub type Position = u8;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct PositionHelper {
+    #[serde(deserialize_with = "crate::map_helper::from_str")]
     position: Position,
 }
 
@@ -88,19 +227,34 @@ impl<'de> MapKey<'de> for Position {
     fn from_inner(inner: Self::Inner) -> Self {
         inner.position
     }
+    fn to_inner(&self) -> Self::Inner {
+        PositionHelper { position: *self }
+    }
+    const FIELDS: &'static [&'static str] = &["Position"];
     const CONTAIN_ERROR: &'static str = "a `Position` attribute";
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Parameter {
     #[serde(flatten)]
     pub general: General,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nullability: Option<Nullability>,
     #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub type_: Option<Type>,
     /// Only used on block parameters.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub no_escape: bool,
+    /// Only used on out-parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_count_convention: Option<RetainCountConvention>,
+}
+
+impl_serde_with_general! {
+    Method Property Parameter
 }
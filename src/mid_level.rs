@@ -1,37 +1,118 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+use crate::map_helper::impl_serde_with_general;
+use crate::method_and_property::{expand_properties, find_property};
 use crate::{
-    General, Map, Method, MethodKey, Nullability, Parameter, Position, Property, PropertyKey, Type,
+    General, Kind, Map, Method, MethodKey, Nullability, Parameter, Position, Property, PropertyKey,
+    RetainCountConvention, Type,
 };
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Class {
     #[serde(flatten)]
     pub general: General,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub methods: Map<MethodKey, Method>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub properties: Map<PropertyKey, Property>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub swift_bridge: Option<String>,
+    /// Import a class with lightweight generics, like `NSArray<ObjectType>`,
+    /// without its type parameters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_import_as_non_generic: Option<bool>,
+    /// Expose the members of the class and its subclasses to Objective-C,
+    /// like `@objcMembers` on a Swift class.
+    #[serde(rename = "SwiftObjCMembers")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_objc_members: Option<bool>,
+    /// A Swift protocol that the imported class conforms to, e.g.
+    /// `Swift.Sendable`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_conforms_to: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+impl Class {
+    /// The property with the given name and kind.
+    ///
+    /// Like in clang, an entry without a `PropertyKind` is used for both
+    /// kinds. Clang rejects notes that also have an entry with a kind for
+    /// the same name, see
+    /// [`DiagnosticKind::PropertyKindConflict`][crate::DiagnosticKind::PropertyKindConflict],
+    /// but this returns the entry with the exact kind in that case.
+    pub fn property(&self, name: &str, kind: Kind) -> Option<&Property> {
+        find_property(&self.properties, name, kind)
+    }
+
+    /// Replace each property entry without a `PropertyKind` with an
+    /// instance and a class entry, so that every entry has a kind.
+    ///
+    /// If there's already an entry with a kind, which clang would reject,
+    /// it is kept like in [`property`][Self::property], and its key is
+    /// returned as a conflict.
+    pub fn expand_properties(&mut self) -> Vec<PropertyKey> {
+        expand_properties(&mut self.properties)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Protocol {
     #[serde(flatten)]
     pub general: General,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub methods: Map<MethodKey, Method>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub properties: Map<PropertyKey, Property>,
+    /// Accepted like on classes, but Objective-C protocols have no type
+    /// parameters, so this has no effect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_import_as_non_generic: Option<bool>,
+    /// Accepted like on classes, but Swift only supports `@objcMembers` on
+    /// classes, so this has no effect.
+    #[serde(rename = "SwiftObjCMembers")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_objc_members: Option<bool>,
+    /// A Swift protocol that the imported protocol inherits from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_conforms_to: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+impl Protocol {
+    /// The property with the given name and kind.
+    ///
+    /// Like in clang, an entry without a `PropertyKind` is used for both
+    /// kinds. Clang rejects notes that also have an entry with a kind for
+    /// the same name, see
+    /// [`DiagnosticKind::PropertyKindConflict`][crate::DiagnosticKind::PropertyKindConflict],
+    /// but this returns the entry with the exact kind in that case.
+    pub fn property(&self, name: &str, kind: Kind) -> Option<&Property> {
+        find_property(&self.properties, name, kind)
+    }
+
+    /// Replace each property entry without a `PropertyKind` with an
+    /// instance and a class entry, so that every entry has a kind.
+    ///
+    /// If there's already an entry with a kind, which clang would reject,
+    /// it is kept like in [`property`][Self::property], and its key is
+    /// returned as a conflict.
+    pub fn expand_properties(&mut self) -> Vec<PropertyKey> {
+        expand_properties(&mut self.properties)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum EnumKind {
     #[serde(alias = "CFEnum")]
     NSEnum,
@@ -43,9 +124,20 @@ pub enum EnumKind {
     None,
 }
 
+/// Whether new cases may be added to an enum, like
+/// `__attribute__((enum_extensibility(...)))`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnumExtensibility {
+    Open,
+    Closed,
+    None,
+}
+
 /// Structs, enums, and unions.
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Tag {
@@ -53,12 +145,79 @@ pub struct Tag {
     pub general: General,
     /// Only used on enums.
     #[serde(rename = "NSErrorDomain")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error_domain: Option<String>,
     /// Only used on enums.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub enum_kind: Option<EnumKind>,
+    /// Only used on enums. Can't be combined with `enum_kind`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enum_extensibility: Option<EnumExtensibility>,
+    /// Only used on enums. Can't be combined with `enum_kind`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag_enum: Option<bool>,
+    /// How a C++ type is imported into Swift.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_import_as: Option<SwiftImportAs>,
+    /// The function that retains an instance of a reference type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_retain_op: Option<String>,
+    /// The function that releases an instance of a reference type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_release_op: Option<String>,
+    /// The function that destroys an instance of a non-copyable type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_destroy_op: Option<String>,
+    /// A Swift protocol that the imported struct, enum or class conforms
+    /// to, e.g. `Swift.Hashable`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_conforms_to: Option<String>,
+    /// Whether the type is `Copyable` in Swift, `None` if not specified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_copyable: Option<bool>,
+    /// Whether the type is `Escapable` in Swift, `None` if not specified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swift_escapable: Option<bool>,
+    /// Only used on C++ records.
+    #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub methods: Map<String, Function>,
+    /// Only used on C++ records.
+    #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Field>,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize)]
+/// Fields of C++ records.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
+#[serde(default)]
+#[non_exhaustive]
+pub struct Field {
+    #[serde(flatten)]
+    pub general: General,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullability: Option<Nullability>,
+    #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_: Option<Type>,
+}
+
+/// How a C++ type is imported into Swift.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwiftImportAs {
+    /// A reference type, i.e. a Swift class, whose lifetime is managed
+    /// with `SwiftRetainOp` and `SwiftReleaseOp`.
+    Reference,
+    /// A value type, i.e. a Swift struct.
+    Value,
+    /// A value type that owns its contents.
+    Owned,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SwiftWrapper {
     Struct,
@@ -66,31 +225,37 @@ pub enum SwiftWrapper {
     None,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Typedef {
     #[serde(flatten)]
     pub general: General,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub swift_wrapper: Option<SwiftWrapper>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Global {
     #[serde(flatten)]
     pub general: General,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nullability: Option<Nullability>,
     #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub type_: Option<Type>,
 }
 
 /// Enum cases.
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Enumerator {
@@ -98,17 +263,26 @@ pub struct Enumerator {
     pub general: General,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
+#[serde(remote = "Self")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Function {
     #[serde(flatten)]
     pub general: General,
     /// Note: May not work due to compiler bugs
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nullability_of_ret: Option<Nullability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub result_type: Option<Type>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub parameters: Map<Position, Parameter>,
-    // pub retain_count_convention:
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retain_count_convention: Option<RetainCountConvention>,
+}
+
+impl_serde_with_general! {
+    Class Protocol Tag Field Typedef Global Enumerator Function
 }
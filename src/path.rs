@@ -0,0 +1,79 @@
+//! Paths to entries and attributes, such as
+//! `Classes[A].Methods[-initWithA:].Parameters[0].Nullability`.
+//!
+//! These are used in [`Diagnostic`], [`Change`] and [`Conflict`], and can be
+//! resolved in a [`Document`] with [`Document::locate`], or in the notes with
+//! [`ApiNotes::select`].
+//!
+//! [`ApiNotes::select`]: crate::ApiNotes::select
+//! [`Diagnostic`]: crate::Diagnostic
+//! [`Change`]: crate::Change
+//! [`Conflict`]: crate::Conflict
+//! [`Document`]: crate::Document
+//! [`Document::locate`]: crate::Document::locate
+use crate::Version;
+
+/// A step in a path, e.g. `Methods[-init]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Segment<'a> {
+    /// The attribute, e.g. `Methods`.
+    pub(crate) key: &'a str,
+    /// The label of the entry in the list of entries under `key`, e.g.
+    /// `-init`.
+    pub(crate) label: Option<&'a str>,
+}
+
+/// Split a path into its segments.
+pub(crate) fn parse(path: &str) -> Option<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        let end = rest.find(['.', '[']).unwrap_or(rest.len());
+        let (key, after) = rest.split_at(end);
+        if key.is_empty() {
+            return None;
+        }
+        rest = after;
+
+        let mut label = None;
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            label = Some(&after[..end]);
+            rest = &after[end + 1..];
+        }
+        segments.push(Segment { key, label });
+
+        if !rest.is_empty() {
+            rest = rest.strip_prefix('.')?;
+        }
+    }
+    Some(segments)
+}
+
+/// The label of an entry, e.g. `A` for a class, `-initWithA:` for an
+/// instance method, `0` for a parameter or `3.0` for a `SwiftVersions`
+/// section.
+///
+/// `field` returns the text of the given scalar attribute of the entry.
+pub(crate) fn label(field: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let kind = |key: &str| match field(key).as_deref() {
+        Some("Instance") => "-",
+        Some("Class") => "+",
+        _ => "",
+    };
+    if let Some(selector) = field("Selector") {
+        return Some(format!("{}{selector}", kind("MethodKind")));
+    }
+    if let Some(name) = field("Name") {
+        return Some(format!("{}{name}", kind("PropertyKind")));
+    }
+    if let Some(position) = field("Position") {
+        return Some(position);
+    }
+    let version = field("Version")?;
+    // Normalize e.g. `3` to `3.0`.
+    Some(match version.parse::<Version>() {
+        Ok(version) => version.to_string(),
+        Err(_) => version,
+    })
+}
@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+
+use crate::resolve::with_type_overrides;
+use crate::{
+    ApiNotes, Class, Data, Enumerator, Field, Function, Global, Kind, Map, Method, MethodKey,
+    Namespace, Property, Protocol, Tag, Typedef, Version,
+};
+
+/// Lookups of the notes for individual declarations, see
+/// [`ApiNotes::query`].
+///
+/// Names of C++ entities can be qualified with their namespace, e.g.
+/// `A::B::f`.
+#[derive(Clone, Copy, Debug)]
+pub struct Query<'a> {
+    notes: &'a ApiNotes,
+    version: Option<Version>,
+}
+
+impl ApiNotes {
+    /// Look up the notes for individual declarations, as they apply when
+    /// importing with the given Swift language version.
+    ///
+    /// The results are the same as the entities in
+    /// [`for_swift_version`][Self::for_swift_version], but only the entities
+    /// that are looked up are resolved, and they're only copied when their
+    /// nullability has to be dropped because of a type override.
+    ///
+    /// Classes, protocols, tags and namespaces are the exception: they're
+    /// returned as written in the section they're found in, so use the
+    /// lookups for their members, like [`method`][Query::method], instead.
+    ///
+    /// ```
+    /// use apinotes::{ApiNotes, Kind, Version};
+    ///
+    /// let notes: ApiNotes = "\
+    /// Name: AppKit
+    /// Classes:
+    ///   - Name: NSView
+    ///     Methods:
+    ///       - Selector: 'initWithFrame:'
+    ///         MethodKind: Instance
+    ///         DesignatedInit: true
+    /// SwiftVersions:
+    ///   - Version: 4
+    ///     Classes:
+    ///       - Name: NSView
+    ///         SwiftName: View
+    /// ".parse().unwrap();
+    ///
+    /// let query = notes.query(Some(Version::new(4, 0, 0)));
+    /// let method = query.method("NSView", "initWithFrame:", Kind::Instance).unwrap();
+    /// assert!(method.designated_init);
+    /// assert!(query.class("NSView").unwrap().general.swift_name.is_some());
+    /// assert!(notes.query(None).class("NSView").unwrap().general.swift_name.is_none());
+    /// ```
+    pub fn query(&self, version: Option<Version>) -> Query<'_> {
+        Query {
+            notes: self,
+            version,
+        }
+    }
+}
+
+impl<'a> Query<'a> {
+    /// The notes that are searched, most specific first.
+    fn layers(&self) -> impl Iterator<Item = &'a Data> {
+        let notes = self.notes;
+        notes
+            .sections_for(self.version)
+            .into_iter()
+            .chain([&notes.data])
+    }
+
+    /// Find `name` in the entries returned by `entries`, in the most specific
+    /// layer that has it.
+    fn find<T>(
+        &self,
+        name: &str,
+        entries: impl Fn(&'a Data) -> &'a Map<String, T>,
+    ) -> Option<&'a T> {
+        self.layers().find_map(|data| find_in(data, name, &entries))
+    }
+
+    /// An Objective-C class.
+    ///
+    /// The class is returned as written, and in a `SwiftVersions` section
+    /// only contains the members that it overrides, so use
+    /// [`method`][Self::method] and [`property`][Self::property] to look up
+    /// members.
+    pub fn class(&self, name: &str) -> Option<&'a Class> {
+        self.find(name, |data| &data.classes)
+    }
+
+    /// An Objective-C protocol.
+    ///
+    /// Like with [`class`][Self::class], use
+    /// [`protocol_method`][Self::protocol_method] and
+    /// [`protocol_property`][Self::protocol_property] to look up members.
+    pub fn protocol(&self, name: &str) -> Option<&'a Protocol> {
+        self.find(name, |data| &data.protocols)
+    }
+
+    /// A method of an Objective-C class.
+    pub fn method(&self, class: &str, selector: &str, kind: Kind) -> Option<Cow<'a, Method>> {
+        let key = MethodKey {
+            selector: selector.parse().ok()?,
+            kind,
+        };
+        self.layers()
+            .find_map(|data| data.classes.get(class)?.methods.get(&key))
+            .map(with_type_overrides)
+    }
+
+    /// A method of an Objective-C protocol.
+    pub fn protocol_method(
+        &self,
+        protocol: &str,
+        selector: &str,
+        kind: Kind,
+    ) -> Option<Cow<'a, Method>> {
+        let key = MethodKey {
+            selector: selector.parse().ok()?,
+            kind,
+        };
+        self.layers()
+            .find_map(|data| data.protocols.get(protocol)?.methods.get(&key))
+            .map(with_type_overrides)
+    }
+
+    /// A property of an Objective-C class, see [`Class::property`].
+    pub fn property(&self, class: &str, name: &str, kind: Kind) -> Option<Cow<'a, Property>> {
+        self.layers()
+            .find_map(|data| data.classes.get(class)?.property(name, kind.clone()))
+            .map(with_type_overrides)
+    }
+
+    /// A property of an Objective-C protocol, see [`Protocol::property`].
+    pub fn protocol_property(
+        &self,
+        protocol: &str,
+        name: &str,
+        kind: Kind,
+    ) -> Option<Cow<'a, Property>> {
+        self.layers()
+            .find_map(|data| data.protocols.get(protocol)?.property(name, kind.clone()))
+            .map(with_type_overrides)
+    }
+
+    /// A struct, enum or union.
+    ///
+    /// Like with [`class`][Self::class], use [`tag_method`][Self::tag_method]
+    /// and [`tag_field`][Self::tag_field] to look up members.
+    pub fn tag(&self, name: &str) -> Option<&'a Tag> {
+        self.find(name, |data| &data.tags)
+    }
+
+    /// A method of a C++ record.
+    pub fn tag_method(&self, tag: &str, name: &str) -> Option<Cow<'a, Function>> {
+        self.layers()
+            .find_map(|data| find_in(data, tag, |data| &data.tags)?.methods.get(name))
+            .map(with_type_overrides)
+    }
+
+    /// A field of a C++ record.
+    pub fn tag_field(&self, tag: &str, name: &str) -> Option<Cow<'a, Field>> {
+        self.layers()
+            .find_map(|data| find_in(data, tag, |data| &data.tags)?.fields.get(name))
+            .map(with_type_overrides)
+    }
+
+    /// A typedef.
+    pub fn typedef(&self, name: &str) -> Option<&'a Typedef> {
+        self.find(name, |data| &data.typedefs)
+    }
+
+    /// A global variable.
+    pub fn global(&self, name: &str) -> Option<Cow<'a, Global>> {
+        self.find(name, |data| &data.globals)
+            .map(with_type_overrides)
+    }
+
+    /// An enum case.
+    pub fn enumerator(&self, name: &str) -> Option<&'a Enumerator> {
+        self.find(name, |data| &data.enumerators)
+    }
+
+    /// A function.
+    pub fn function(&self, name: &str) -> Option<Cow<'a, Function>> {
+        self.find(name, |data| &data.functions)
+            .map(with_type_overrides)
+    }
+
+    /// A C++ namespace, e.g. `A::B`.
+    ///
+    /// Like with [`class`][Self::class], a namespace in a `SwiftVersions`
+    /// section only contains the entities that it overrides.
+    pub fn namespace(&self, name: &str) -> Option<&'a Namespace> {
+        self.layers().find_map(|data| data.namespace(name))
+    }
+}
+
+/// Find `name` in the entries of `data` returned by `entries`, looking in the
+/// namespace that it is qualified with, if any.
+fn find_in<'a, T>(
+    data: &'a Data,
+    name: &str,
+    entries: impl Fn(&'a Data) -> &'a Map<String, T>,
+) -> Option<&'a T> {
+    match name.rsplit_once("::") {
+        Some((namespace, name)) => entries(&data.namespace(namespace)?.data).get(name),
+        None => entries(data).get(name),
+    }
+}
@@ -0,0 +1,269 @@
+use std::borrow::Cow;
+use std::mem;
+
+use crate::{
+    ApiNotes, Data, Field, Function, Global, Kind, Map, Method, MethodKey, Parameter, Property,
+    PropertyKey, Type, Version,
+};
+
+impl ApiNotes {
+    /// Compute the notes that apply when importing with the given Swift
+    /// language version.
+    ///
+    /// Like clang, this picks the notes for each entity (class, method,
+    /// property, function, ...) separately: an entity is taken from the
+    /// `SwiftVersions` section with the smallest version that is at least
+    /// `version` and mentions it, and replaces the unversioned entity
+    /// entirely. Entities that no such section mentions are kept as-is. So
+    /// with sections for Swift 5 and 6, the notes for Swift 5.9 come from
+    /// the Swift 6 section where it has them.
+    ///
+    /// The members of classes, protocols and C++ records are separate
+    /// entities, so they are kept unless overridden themselves. Namespaces
+    /// are resolved the same way, recursively.
+    ///
+    /// Additionally, as documented on [`Nullability`][crate::Nullability],
+    /// the nullability of an entity is dropped when it also has a type
+    /// override, since the type then determines the nullability.
+    pub fn for_swift_version(&self, version: Version) -> Data {
+        let mut data = self.data.clone();
+        // Overlay the largest version first, so that the smallest one that
+        // mentions an entity wins.
+        for versioned in self.sections_for(Some(version)).into_iter().rev() {
+            data.overlay(versioned);
+        }
+        data.apply_type_overrides();
+        data
+    }
+
+    /// The `SwiftVersions` sections that apply for `version`, smallest
+    /// version first.
+    pub(crate) fn sections_for(&self, version: Option<Version>) -> Vec<&Data> {
+        let Some(version) = version else {
+            return Vec::new();
+        };
+        let mut sections: Vec<_> = self
+            .swift_versions
+            .iter()
+            .filter(|(section, _)| **section >= version)
+            .collect();
+        sections.sort_by_key(|(section, _)| **section);
+        sections.into_iter().map(|(_, data)| data).collect()
+    }
+}
+
+impl Data {
+    /// Replace the entities in `self` with those in `versioned`.
+    fn overlay(&mut self, versioned: &Data) {
+        for (name, class) in &versioned.classes {
+            let existing = self.classes.entry(name.clone()).or_default();
+            let mut new = class.clone();
+            overlay_members(
+                &mut new.methods,
+                &mut new.properties,
+                &mut existing.methods,
+                &mut existing.properties,
+            );
+            *existing = new;
+        }
+        for (name, protocol) in &versioned.protocols {
+            let existing = self.protocols.entry(name.clone()).or_default();
+            let mut new = protocol.clone();
+            overlay_members(
+                &mut new.methods,
+                &mut new.properties,
+                &mut existing.methods,
+                &mut existing.properties,
+            );
+            *existing = new;
+        }
+        for (name, tag) in &versioned.tags {
+            let existing = self.tags.entry(name.clone()).or_default();
+            let mut new = tag.clone();
+            for (name, method) in mem::take(&mut existing.methods) {
+                new.methods.entry(name).or_insert(method);
+            }
+            for (name, field) in mem::take(&mut existing.fields) {
+                new.fields.entry(name).or_insert(field);
+            }
+            *existing = new;
+        }
+        overlay_map(&mut self.typedefs, &versioned.typedefs);
+        overlay_map(&mut self.globals, &versioned.globals);
+        overlay_map(&mut self.enumerators, &versioned.enumerators);
+        overlay_map(&mut self.functions, &versioned.functions);
+        for (name, namespace) in &versioned.namespaces {
+            let existing = self.namespaces.entry(name.clone()).or_default();
+            existing.general = namespace.general.clone();
+            existing.data.overlay(&namespace.data);
+        }
+    }
+
+    fn apply_type_overrides(&mut self) {
+        for class in self.classes.values_mut() {
+            class.methods.values_mut().for_each(TypeOverrides::apply);
+            class.properties.values_mut().for_each(TypeOverrides::apply);
+        }
+        for protocol in self.protocols.values_mut() {
+            protocol.methods.values_mut().for_each(TypeOverrides::apply);
+            protocol
+                .properties
+                .values_mut()
+                .for_each(TypeOverrides::apply);
+        }
+        for tag in self.tags.values_mut() {
+            tag.methods.values_mut().for_each(TypeOverrides::apply);
+            tag.fields.values_mut().for_each(TypeOverrides::apply);
+        }
+        self.globals.values_mut().for_each(TypeOverrides::apply);
+        self.functions.values_mut().for_each(TypeOverrides::apply);
+        for namespace in self.namespaces.values_mut() {
+            namespace.data.apply_type_overrides();
+        }
+    }
+}
+
+fn overlay_map<V: Clone>(map: &mut Map<String, V>, versioned: &Map<String, V>) {
+    for (name, value) in versioned {
+        map.insert(name.clone(), value.clone());
+    }
+}
+
+/// Move the unversioned members into `new`, the versioned class or
+/// protocol, unless they are overridden by it.
+fn overlay_members(
+    new_methods: &mut Map<MethodKey, Method>,
+    new_properties: &mut Map<PropertyKey, Property>,
+    methods: &mut Map<MethodKey, Method>,
+    properties: &mut Map<PropertyKey, Property>,
+) {
+    for (key, method) in mem::take(methods) {
+        new_methods.entry(key).or_insert(method);
+    }
+
+    for (key, property) in mem::take(properties) {
+        let key_with_kind = |kind| PropertyKey {
+            name: key.name.clone(),
+            kind,
+        };
+        if new_properties.contains_key(&key_with_kind(None)) {
+            continue;
+        }
+        match key.kind {
+            Some(_) => {
+                new_properties.entry(key).or_insert(property);
+            }
+            // A property without a kind applies to both instance and class
+            // properties, so split it up if only one of them is overridden.
+            None => {
+                let remaining: Vec<_> = [Kind::Instance, Kind::Class]
+                    .into_iter()
+                    .filter(|kind| !new_properties.contains_key(&key_with_kind(Some(kind.clone()))))
+                    .collect();
+                if remaining.len() == 2 {
+                    new_properties.insert(key, property);
+                } else {
+                    for kind in remaining {
+                        new_properties.insert(key_with_kind(Some(kind)), property.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Entities whose nullability is dropped when they have a type override.
+pub(crate) trait TypeOverrides: Clone {
+    /// Whether the entity or one of its parameters has both a nullability
+    /// and a type override.
+    fn is_overridden(&self) -> bool;
+
+    /// Drop the nullability of the entity and its parameters where they
+    /// have a type override.
+    fn apply(&mut self);
+}
+
+/// The entity with [`TypeOverrides`] applied, only copying it if needed.
+pub(crate) fn with_type_overrides<T: TypeOverrides>(entity: &T) -> Cow<'_, T> {
+    if entity.is_overridden() {
+        let mut entity = entity.clone();
+        entity.apply();
+        Cow::Owned(entity)
+    } else {
+        Cow::Borrowed(entity)
+    }
+}
+
+fn is_overridden<T>(type_: &Option<Type>, nullability: &Option<T>) -> bool {
+    type_.is_some() && nullability.is_some()
+}
+
+fn apply<T>(type_: &Option<Type>, nullability: &mut Option<T>) {
+    if type_.is_some() {
+        *nullability = None;
+    }
+}
+
+impl TypeOverrides for Parameter {
+    fn is_overridden(&self) -> bool {
+        is_overridden(&self.type_, &self.nullability)
+    }
+
+    fn apply(&mut self) {
+        apply(&self.type_, &mut self.nullability);
+    }
+}
+
+impl TypeOverrides for Method {
+    fn is_overridden(&self) -> bool {
+        is_overridden(&self.result_type, &self.nullability_of_ret)
+            || self.parameters.values().any(Parameter::is_overridden)
+    }
+
+    fn apply(&mut self) {
+        apply(&self.result_type, &mut self.nullability_of_ret);
+        self.parameters.values_mut().for_each(Parameter::apply);
+    }
+}
+
+impl TypeOverrides for Function {
+    fn is_overridden(&self) -> bool {
+        is_overridden(&self.result_type, &self.nullability_of_ret)
+            || self.parameters.values().any(Parameter::is_overridden)
+    }
+
+    fn apply(&mut self) {
+        apply(&self.result_type, &mut self.nullability_of_ret);
+        self.parameters.values_mut().for_each(Parameter::apply);
+    }
+}
+
+impl TypeOverrides for Property {
+    fn is_overridden(&self) -> bool {
+        is_overridden(&self.type_, &self.nullability)
+    }
+
+    fn apply(&mut self) {
+        apply(&self.type_, &mut self.nullability);
+    }
+}
+
+impl TypeOverrides for Global {
+    fn is_overridden(&self) -> bool {
+        is_overridden(&self.type_, &self.nullability)
+    }
+
+    fn apply(&mut self) {
+        apply(&self.type_, &mut self.nullability);
+    }
+}
+
+impl TypeOverrides for Field {
+    fn is_overridden(&self) -> bool {
+        is_overridden(&self.type_, &self.nullability)
+    }
+
+    fn apply(&mut self) {
+        apply(&self.type_, &mut self.nullability);
+    }
+}
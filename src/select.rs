@@ -0,0 +1,112 @@
+use serde_yaml::Value;
+
+use crate::diff::{entries, join};
+use crate::format::key_order;
+use crate::{path, ApiNotes, Error, ErrorKind, Version};
+
+/// A node matched by [`ApiNotes::select`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Selection {
+    /// The path of the node, with any wildcards filled in, e.g.
+    /// `Classes[A].Methods[-initWithA:].SwiftName`.
+    pub path: String,
+    /// The node, as it would be written in the notes.
+    pub value: Value,
+}
+
+impl ApiNotes {
+    /// Find the entries and attributes that match a path such as
+    /// `Classes[A].Methods[-initWithA:].SwiftName`.
+    ///
+    /// Like the paths in e.g. [`Diagnostic`][crate::Diagnostic], instance
+    /// methods and properties are prefixed with `-` and class ones with `+`,
+    /// though the prefix can be left out to match both. In addition, `*`
+    /// matches any entry or attribute, e.g. `Classes[*].Properties[*]`.
+    ///
+    /// Returns an error if the path is invalid, and the matches in the order
+    /// they would be written in the canonical layout otherwise.
+    ///
+    /// ```
+    /// let notes: apinotes::ApiNotes = "\
+    /// Name: SomeKit
+    /// Classes:
+    ///   - Name: A
+    ///     SwiftName: AA
+    ///   - Name: B
+    ///     Properties:
+    ///       - Name: value
+    ///         PropertyKind: Instance
+    /// ".parse().unwrap();
+    ///
+    /// let selections = notes.select("Classes[*].SwiftName").unwrap();
+    /// assert_eq!(selections.len(), 1);
+    /// assert_eq!(selections[0].path, "Classes[A].SwiftName");
+    /// assert_eq!(selections[0].value, "AA");
+    ///
+    /// let selections = notes.select("Classes[B].Properties[value]").unwrap();
+    /// assert_eq!(selections[0].path, "Classes[B].Properties[-value]");
+    /// ```
+    pub fn select(&self, path: &str) -> Result<Vec<Selection>, Error> {
+        let segments = path::parse(path)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidValue, format!("invalid path `{path}`")))?;
+        let root = serde_yaml::to_value(self).expect("notes can always be serialized");
+
+        let mut matches = vec![(String::new(), &root)];
+        for segment in segments {
+            let mut next = Vec::new();
+            for (parent, value) in matches {
+                let Some(mapping) = value.as_mapping() else {
+                    continue;
+                };
+                let mut attributes: Vec<_> = mapping.iter().collect();
+                // Stable, like when writing, so that unknown attributes keep
+                // their order.
+                attributes.sort_by_key(|(key, _)| key_order(key));
+                for (key, value) in attributes {
+                    let Some(key) = key.as_str() else {
+                        continue;
+                    };
+                    if segment.key != "*" && segment.key != key {
+                        continue;
+                    }
+                    let path = join(&parent, key);
+                    let Some(pattern) = segment.label else {
+                        next.push((path, value));
+                        continue;
+                    };
+                    let items = value.as_sequence().map(Vec::as_slice).unwrap_or_default();
+                    let labels = entries(value).unwrap_or_default();
+                    for ((label, _), item) in labels.into_iter().zip(items) {
+                        if label_matches(pattern, &label) {
+                            next.push((format!("{path}[{label}]"), item));
+                        }
+                    }
+                }
+            }
+            matches = next;
+        }
+
+        Ok(matches
+            .into_iter()
+            .map(|(path, value)| Selection {
+                path,
+                value: value.clone(),
+            })
+            .collect())
+    }
+}
+
+fn label_matches(pattern: &str, label: &str) -> bool {
+    if pattern == "*" || pattern == label {
+        return true;
+    }
+    // The kind of a method or property may be left out.
+    if label.strip_prefix(['-', '+']) == Some(pattern) {
+        return true;
+    }
+    // Versions may be written as e.g. `3` instead of `3.0`.
+    pattern
+        .parse::<Version>()
+        .is_ok_and(|version| version.to_string() == label)
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Serialize};
+
+use crate::map_helper;
+use crate::{Error, ErrorKind};
+
+/// An Objective-C selector, such as `description` or `initWithFrame:`.
+///
+/// Selectors either consist of a single identifier and take no arguments,
+/// or consist of one or more identifiers each followed by a colon, one for
+/// each argument.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Selector {
+    name: String,
+}
+
+impl Selector {
+    /// The selector as written, e.g. `initWithFrame:`.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of arguments that a method with this selector takes.
+    pub fn num_args(&self) -> usize {
+        self.name.matches(':').count()
+    }
+
+    /// The keyword pieces of the selector, without the colons.
+    ///
+    /// A selector that takes no arguments has a single piece.
+    ///
+    /// ```
+    /// let selector: apinotes::Selector = "initWithFrame:style:".parse().unwrap();
+    /// assert_eq!(selector.pieces().collect::<Vec<_>>(), ["initWithFrame", "style"]);
+    /// ```
+    pub fn pieces(&self) -> impl Iterator<Item = &str> + '_ {
+        self.name.strip_suffix(':').unwrap_or(&self.name).split(':')
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn validate(s: &str) -> Result<(), &'static str> {
+    if s.is_empty() {
+        return Err("selector is empty");
+    }
+    let pieces = match s.strip_suffix(':') {
+        Some(pieces) => pieces,
+        None if s.contains(':') => return Err("selector with arguments must end with a colon"),
+        None => s,
+    };
+    for piece in pieces.split(':') {
+        if piece.is_empty() {
+            return Err("selector has an empty piece");
+        }
+        if !is_identifier(piece) {
+            return Err("selector piece is not an identifier");
+        }
+    }
+    Ok(())
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        validate(s).map_err(|reason| {
+            Error::new(
+                ErrorKind::InvalidValue,
+                format!("invalid selector `{s}`: {reason}"),
+            )
+        })?;
+        Ok(Self { name: s.into() })
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl<'de> Deserialize<'de> for Selector {
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        map_helper::parse_str(des, |s| {
+            validate(s)?;
+            Ok::<_, &str>(Self { name: s.into() })
+        })
+    }
+}
+
+impl Serialize for Selector {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_str(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for invalid in ["", "a:b", ":", "a::", "a b:", "1a"] {
+            assert!(invalid.parse::<Selector>().is_err(), "{invalid:?}");
+        }
+        let selector: Selector = "initWithA:b:".parse().unwrap();
+        assert_eq!(selector.num_args(), 2);
+        let selector: Selector = "init".parse().unwrap();
+        assert_eq!(selector.num_args(), 0);
+        assert_eq!(selector.pieces().collect::<Vec<_>>(), ["init"]);
+    }
+}
@@ -0,0 +1,244 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Serialize};
+
+use crate::map_helper;
+use crate::{Error, ErrorKind};
+
+/// A parsed `SwiftName` attribute.
+///
+/// Examples of Swift names are:
+/// - `foo`: A plain name.
+/// - `foo(bar:_:)`: A function name with argument labels.
+/// - `Type.member`: A name imported as a member of `Type`.
+/// - `init(x:)`: An initializer.
+/// - `getter:Type.prop(self:)`: A getter for a property on `Type`.
+///
+/// ```
+/// let name: apinotes::SwiftName = "getter:Type.prop(self:)".parse().unwrap();
+/// assert_eq!(name.kind(), apinotes::SwiftNameKind::Getter);
+/// assert_eq!(name.context.as_deref(), Some("Type"));
+/// assert_eq!(name.base_name, "prop");
+/// assert_eq!(name.to_string(), "getter:Type.prop(self:)");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SwiftName {
+    /// Whether the declaration is imported as a property accessor.
+    pub accessor: Option<Accessor>,
+    /// The type that the declaration is imported as a member of, e.g. `Type`
+    /// in `Type.member`. May contain dots for nested types.
+    pub context: Option<String>,
+    /// The name itself, e.g. `foo` in `foo(bar:)`.
+    pub base_name: String,
+    /// The argument labels, with `None` for the `_` label.
+    ///
+    /// This is `None` if the name is not a function name, i.e. if it has no
+    /// parentheses.
+    pub arguments: Option<Vec<Option<String>>>,
+}
+
+/// A property accessor prefix in a [`SwiftName`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Accessor {
+    /// `getter:`
+    Getter,
+    /// `setter:`
+    Setter,
+}
+
+/// What a [`SwiftName`] imports a declaration as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SwiftNameKind {
+    /// A plain name without arguments, e.g. a type, variable or enum case.
+    Name,
+    /// A function or method.
+    Function,
+    /// An initializer.
+    Initializer,
+    /// A property getter.
+    Getter,
+    /// A property setter.
+    Setter,
+}
+
+impl SwiftName {
+    /// A plain name without a context or arguments.
+    pub fn new(base_name: impl Into<String>) -> Self {
+        Self {
+            accessor: None,
+            context: None,
+            base_name: base_name.into(),
+            arguments: None,
+        }
+    }
+
+    /// What the name imports the declaration as.
+    pub fn kind(&self) -> SwiftNameKind {
+        match (self.accessor, &self.arguments) {
+            (Some(Accessor::Getter), _) => SwiftNameKind::Getter,
+            (Some(Accessor::Setter), _) => SwiftNameKind::Setter,
+            (None, Some(_)) if self.base_name == "init" => SwiftNameKind::Initializer,
+            (None, Some(_)) => SwiftNameKind::Function,
+            (None, None) => SwiftNameKind::Name,
+        }
+    }
+
+    /// The number of argument labels, if this is a function name.
+    pub fn num_args(&self) -> Option<usize> {
+        self.arguments.as_ref().map(Vec::len)
+    }
+
+    /// The index of the `self:` argument label, if any.
+    ///
+    /// This is used for C functions that are imported as instance members.
+    pub fn self_index(&self) -> Option<usize> {
+        self.arguments
+            .as_ref()?
+            .iter()
+            .position(|label| label.as_deref() == Some("self"))
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty()
+        && !s
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | ':' | '.' | ','))
+}
+
+fn parse(s: &str) -> Result<SwiftName, &'static str> {
+    let (accessor, rest) = if let Some(rest) = s.strip_prefix("getter:") {
+        (Some(Accessor::Getter), rest)
+    } else if let Some(rest) = s.strip_prefix("setter:") {
+        (Some(Accessor::Setter), rest)
+    } else {
+        (None, s)
+    };
+
+    let (name, arguments) = match rest.split_once('(') {
+        Some((name, arguments)) => {
+            let arguments = arguments
+                .strip_suffix(')')
+                .ok_or("expected `)` at the end of the argument labels")?;
+            let labels = match arguments.strip_suffix(':') {
+                Some(labels) => labels
+                    .split(':')
+                    .map(|label| match label {
+                        "_" => Ok(None),
+                        label if is_identifier(label) => Ok(Some(label.to_string())),
+                        _ => Err("invalid argument label"),
+                    })
+                    .collect::<Result<_, _>>()?,
+                None if arguments.is_empty() => Vec::new(),
+                None => return Err("argument labels must each be followed by a colon"),
+            };
+            (name, Some(labels))
+        }
+        None if rest.contains(')') => return Err("unexpected `)`"),
+        None => (rest, None),
+    };
+
+    let (context, base_name) = match name.rsplit_once('.') {
+        Some((context, base_name)) => {
+            if !context.split('.').all(is_identifier) {
+                return Err("invalid context type");
+            }
+            (Some(context.to_string()), base_name)
+        }
+        None => (None, name),
+    };
+    if !is_identifier(base_name) {
+        return Err("invalid base name");
+    }
+
+    Ok(SwiftName {
+        accessor,
+        context,
+        base_name: base_name.into(),
+        arguments,
+    })
+}
+
+impl FromStr for SwiftName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map_err(|reason| {
+            Error::new(
+                ErrorKind::InvalidValue,
+                format!("invalid Swift name `{s}`: {reason}"),
+            )
+        })
+    }
+}
+
+impl fmt::Display for SwiftName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.accessor {
+            Some(Accessor::Getter) => f.write_str("getter:")?,
+            Some(Accessor::Setter) => f.write_str("setter:")?,
+            None => {}
+        }
+        if let Some(context) = &self.context {
+            write!(f, "{context}.")?;
+        }
+        f.write_str(&self.base_name)?;
+        if let Some(arguments) = &self.arguments {
+            f.write_str("(")?;
+            for label in arguments {
+                write!(f, "{}:", label.as_deref().unwrap_or("_"))?;
+            }
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for SwiftName {
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        map_helper::parse_str(des, parse)
+    }
+}
+
+impl Serialize for SwiftName {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for invalid in ["", "f(", "f(a)", "f(a::)", ".f", "A..f", "f()x"] {
+            assert!(invalid.parse::<SwiftName>().is_err(), "{invalid:?}");
+        }
+        for valid in [
+            "f",
+            "f()",
+            "f(_:b:)",
+            "A.B.f(x:)",
+            "init(x:)",
+            "getter:A.p(self:)",
+            "setter:p(_:)",
+        ] {
+            let name: SwiftName = valid.parse().unwrap();
+            assert_eq!(name.to_string(), valid);
+        }
+
+        let name: SwiftName = "A.init(_:b:)".parse().unwrap();
+        assert_eq!(name.kind(), SwiftNameKind::Initializer);
+        assert_eq!(name.context.as_deref(), Some("A"));
+        assert_eq!(name.arguments, Some(vec![None, Some("b".into())]));
+        let name: SwiftName = "setter:A.p(self:_:)".parse().unwrap();
+        assert_eq!(name.kind(), SwiftNameKind::Setter);
+        assert_eq!(name.self_index(), Some(0));
+    }
+}
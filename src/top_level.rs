@@ -1,28 +1,138 @@
+use std::fmt;
+use std::io;
 use std::path::Path;
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::de::{self, Error as _};
+use serde::{Deserialize, Serialize};
 
-use crate::map_helper::MapKey;
-use crate::{Class, Enumerator, Error, Function, Global, Map, Protocol, Tag, Typedef};
+use crate::error::{self, with_kind};
+use crate::map_helper::{Capture, Flattened, MapKey, Split};
+use crate::{
+    Class, Enumerator, Error, ErrorKind, Function, General, Global, Map, Protocol, Tag, Typedef,
+};
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
 #[non_exhaustive]
 pub struct ApiNotes {
     pub name: String,
-    #[serde(default)]
-    #[serde(with = "crate::map_helper")]
-    pub swift_versions: Map<Version, Data>,
     #[serde(flatten)]
     pub data: Data,
+    #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub swift_versions: Map<Version, Data>,
+}
+
+// Implemented manually instead of with `#[serde(flatten)]`, to preserve the
+// location of errors inside `Data`.
+impl<'de> Deserialize<'de> for ApiNotes {
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Vis;
+
+        impl<'de> de::Visitor<'de> for Vis {
+            type Value = ApiNotes;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map containing a `Name` attribute")
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut top_level = TopLevel::default();
+                let data = Data::deserialize(Split::new(map, &mut top_level))?;
+                Ok(ApiNotes {
+                    name: top_level.name.ok_or_else(|| {
+                        with_kind(ErrorKind::MissingField, A::Error::missing_field("Name"))
+                    })?,
+                    data,
+                    swift_versions: top_level.swift_versions.unwrap_or_default(),
+                })
+            }
+        }
+
+        des.deserialize_map(Vis)
+    }
+}
+
+/// The attributes of [`ApiNotes`] that are not part of [`Data`].
+#[derive(Default)]
+struct TopLevel {
+    name: Option<String>,
+    swift_versions: Option<Map<Version, Data>>,
+}
+
+#[derive(Deserialize)]
+struct SwiftVersions(#[serde(with = "crate::map_helper")] Map<Version, Data>);
+
+impl<'de> Capture<'de> for TopLevel {
+    fn capture<A>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        match key {
+            "Name" => {
+                if self.name.replace(map.next_value()?).is_some() {
+                    return Err(with_kind(
+                        ErrorKind::DuplicateEntry,
+                        A::Error::duplicate_field("Name"),
+                    ));
+                }
+            }
+            "SwiftVersions" => {
+                let SwiftVersions(versions) = map.next_value()?;
+                if self.swift_versions.replace(versions).is_some() {
+                    return Err(with_kind(
+                        ErrorKind::DuplicateEntry,
+                        A::Error::duplicate_field("SwiftVersions"),
+                    ));
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
 }
 
 impl ApiNotes {
+    /// Read and parse the API notes file at the given path.
+    ///
+    /// Errors will include the path.
     pub fn from_path(path: &Path) -> Result<Self, Error> {
-        let contents = std::fs::read_to_string(path).expect("todo");
+        let contents = std::fs::read(path).map_err(|err| Error::from_io(err).with_path(path))?;
+
+        Self::from_slice(&contents).map_err(|err| err.with_path(path))
+    }
 
-        Self::from_str(&contents)
+    /// Read and parse API notes from the given reader.
+    pub fn from_reader<R: io::Read>(mut reader: R) -> Result<Self, Error> {
+        let mut contents = Vec::new();
+        reader.read_to_end(&mut contents).map_err(Error::from_io)?;
+
+        Self::from_slice(&contents)
+    }
+
+    /// Parse API notes from a UTF-8 encoded buffer.
+    pub fn from_slice(contents: &[u8]) -> Result<Self, Error> {
+        let contents =
+            std::str::from_utf8(contents).map_err(|err| Error::from_utf8(contents, err))?;
+
+        Self::from_str(contents)
+    }
+
+    /// Serialize the API notes to a YAML string that clang accepts.
+    pub fn to_string(&self) -> Result<String, Error> {
+        serde_yaml::to_string(self).map_err(Error::from_yaml)
+    }
+
+    /// Serialize the API notes as YAML to the given writer.
+    pub fn to_writer<W: io::Write>(&self, writer: W) -> Result<(), Error> {
+        serde_yaml::to_writer(writer, self).map_err(Error::from_yaml)
     }
 }
 
@@ -30,60 +140,238 @@ impl FromStr for ApiNotes {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        serde_yaml::from_str(s).map_err(Error::from_yaml)
+        error::reset_kind();
+        serde_yaml::from_str(s).map_err(|err| {
+            // Distinguish syntax errors from errors in the structure.
+            if serde_yaml::from_str::<de::IgnoredAny>(s).is_err() {
+                Error::syntax_from_yaml(err)
+            } else {
+                Error::from_yaml(err)
+            }
+        })
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
-#[non_exhaustive]
-pub enum Version {
-    V3,
-    V4,
-    V4_2,
-    V5,
+/// A Swift language version, such as `5.9`, used as the key of a
+/// `SwiftVersions` section.
+///
+/// Missing components are treated as zero, so `5` and `5.0` are equal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
 }
 
-#[derive(Debug, Deserialize)]
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::new(ErrorKind::InvalidValue, format!("invalid version `{s}`"));
+        let mut components = s.trim().split('.').map(|c| c.parse::<u32>());
+        let major = components
+            .next()
+            .ok_or_else(invalid)?
+            .map_err(|_| invalid())?;
+        let minor = components.next().transpose().map_err(|_| invalid())?;
+        let patch = components.next().transpose().map_err(|_| invalid())?;
+        if components.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Self::new(major, minor.unwrap_or(0), patch.unwrap_or(0)))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Vis;
+
+        impl de::Visitor<'_> for Vis {
+            type Value = Version;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a version such as `5.9`")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(|_| {
+                    with_kind(
+                        ErrorKind::InvalidValue,
+                        E::invalid_value(de::Unexpected::Str(v), &self),
+                    )
+                })
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                self.visit_str(&v.to_string())
+            }
+        }
+
+        // Request a string, so that YAML returns versions like `5.10` as
+        // written. Numbers are only accepted from formats that don't have
+        // the text.
+        des.deserialize_str(Vis)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub(crate) struct VersionHelper {
-    version: f32,
+    version: Version,
 }
 
 impl<'de> MapKey<'de> for Version {
     type Inner = VersionHelper;
     fn from_inner(inner: Self::Inner) -> Self {
-        if inner.version == 3.0 {
-            Version::V3
-        } else if inner.version == 4.0 {
-            Version::V4
-        } else if inner.version == 4.2 {
-            Version::V4_2
-        } else if inner.version == 5.0 {
-            Version::V5
-        } else {
-            panic!("unknown version {}", inner.version)
-        }
+        inner.version
+    }
+    fn to_inner(&self) -> Self::Inner {
+        VersionHelper { version: *self }
     }
+    const FIELDS: &'static [&'static str] = &["Version"];
+    const TEXT: bool = true;
     const CONTAIN_ERROR: &'static str = "a `Version` attribute";
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 #[serde(default)]
 #[non_exhaustive]
 pub struct Data {
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub classes: Map<String, Class>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub protocols: Map<String, Protocol>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub tags: Map<String, Tag>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub typedefs: Map<String, Typedef>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub globals: Map<String, Global>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub enumerators: Map<String, Enumerator>,
     #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
     pub functions: Map<String, Function>,
+    /// C++ namespaces.
+    #[serde(with = "crate::map_helper")]
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub namespaces: Map<String, Namespace>,
+}
+
+impl Data {
+    /// Look up a nested namespace by its qualified name, e.g. `A::B`.
+    ///
+    /// ```
+    /// let notes: apinotes::ApiNotes = "\
+    /// Name: SomeKit
+    /// Namespaces:
+    ///   - Name: A
+    ///     Namespaces:
+    ///       - Name: B
+    ///         Tags:
+    ///           - Name: C
+    /// ".parse().unwrap();
+    /// let namespace = notes.data.namespace("A::B").unwrap();
+    /// assert!(namespace.data.tags.contains_key("C"));
+    /// ```
+    pub fn namespace(&self, qualified_name: &str) -> Option<&Namespace> {
+        let mut names = qualified_name.split("::");
+        let mut namespace = self.namespaces.get(names.next()?)?;
+        for name in names {
+            namespace = namespace.data.namespaces.get(name)?;
+        }
+        Some(namespace)
+    }
+
+    /// Look up a nested namespace by its qualified name, e.g. `A::B`.
+    pub fn namespace_mut(&mut self, qualified_name: &str) -> Option<&mut Namespace> {
+        let mut names = qualified_name.split("::");
+        let mut namespace = self.namespaces.get_mut(names.next()?)?;
+        for name in names {
+            namespace = namespace.data.namespaces.get_mut(name)?;
+        }
+        Some(namespace)
+    }
+}
+
+/// A C++ namespace, which can contain the same entities as the top level.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+#[non_exhaustive]
+pub struct Namespace {
+    #[serde(flatten)]
+    pub general: General,
+    #[serde(flatten)]
+    pub data: Data,
+}
+
+// Implemented manually instead of with `#[serde(flatten)]`, to preserve the
+// location of errors inside `Data`.
+impl<'de> Deserialize<'de> for Namespace {
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Vis;
+
+        impl<'de> de::Visitor<'de> for Vis {
+            type Value = Namespace;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut general = Flattened::<General>::new();
+                let data = Data::deserialize(Split::new(map, &mut general))?;
+                let general = general.finish()?;
+                Ok(Namespace { general, data })
+            }
+        }
+
+        des.deserialize_map(Vis)
+    }
 }
@@ -0,0 +1,331 @@
+use std::fmt;
+
+use crate::{
+    ApiNotes, CType, CTypeKind, Data, Function, Kind, Map, Method, MethodKey, Nullability,
+    Parameter, Position, Property, PropertyKey, SwiftName, SwiftNameKind,
+};
+
+/// A problem found by [`ApiNotes::validate`].
+///
+/// Clang mostly ignores notes like these silently, so they usually only show
+/// up as a broken Swift import.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Diagnostic {
+    /// What is wrong.
+    pub kind: DiagnosticKind,
+    /// The entity that the problem was found in, e.g.
+    /// `Classes[A].Methods[-initWithA:b:]`.
+    ///
+    /// Instance methods are prefixed with `-` and class methods with `+`,
+    /// like in Objective-C. Entries in `SwiftVersions` are prefixed with
+    /// e.g. `SwiftVersions[3.0].`.
+    pub path: String,
+}
+
+/// The kind of a [`Diagnostic`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// The `SwiftName` of a method has a different number of argument labels
+    /// than its selector has arguments.
+    ///
+    /// Like in clang, fewer labels are fine if the missing arguments may be
+    /// out-parameters such as `error:`, and a method without arguments may
+    /// be named `init(label:)`.
+    SwiftNameArity {
+        /// The number of arguments of the selector.
+        expected: usize,
+        /// The number of argument labels in the `SwiftName`.
+        found: usize,
+    },
+    /// A `Parameters` entry refers to a position that is not an argument
+    /// of the method or function.
+    ParameterOutOfRange {
+        /// The position of the parameter.
+        position: Position,
+        /// The number of arguments of the method or function.
+        num_args: usize,
+    },
+    /// The legacy `Nullability` list of a method has a different number of
+    /// entries than its selector has arguments.
+    NullabilityArity {
+        /// The number of arguments of the selector.
+        expected: usize,
+        /// The number of entries in the list.
+        found: usize,
+    },
+    /// The legacy `Nullability` list of a method disagrees with the
+    /// `Nullability` of an entry in its `Parameters`.
+    NullabilityConflict {
+        /// The position of the parameter.
+        position: Position,
+        /// The nullability in the legacy list.
+        legacy: Nullability,
+        /// The nullability in the `Parameters` entry, which takes precedence.
+        parameter: Nullability,
+    },
+    /// A tag has an `EnumKind` as well as `EnumExtensibility` or `FlagEnum`,
+    /// which clang rejects.
+    EnumKindConflict,
+    /// A property has an entry without a `PropertyKind` as well as one with
+    /// a kind, which clang rejects as a duplicate.
+    PropertyKindConflict,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        match &self.kind {
+            DiagnosticKind::SwiftNameArity { expected, found } => write!(
+                f,
+                "`SwiftName` has {found} argument label(s), but the selector has {expected} argument(s)"
+            ),
+            DiagnosticKind::ParameterOutOfRange { position, num_args } => write!(
+                f,
+                "parameter position {position} is out of range, there are only {num_args} argument(s)"
+            ),
+            DiagnosticKind::NullabilityArity { expected, found } => write!(
+                f,
+                "`Nullability` has {found} entries, but the selector has {expected} argument(s)"
+            ),
+            DiagnosticKind::NullabilityConflict {
+                position,
+                legacy,
+                parameter,
+            } => write!(
+                f,
+                "`Nullability` of parameter {position} is {parameter:?}, but {legacy:?} in the method's `Nullability` list"
+            ),
+            DiagnosticKind::EnumKindConflict => write!(
+                f,
+                "`EnumKind` can't be combined with `EnumExtensibility` or `FlagEnum`"
+            ),
+            DiagnosticKind::PropertyKindConflict => write!(
+                f,
+                "property has entries both with and without a `PropertyKind`"
+            ),
+        }
+    }
+}
+
+impl ApiNotes {
+    /// Check the notes for inconsistencies that clang would mostly ignore
+    /// silently.
+    ///
+    /// This currently checks that:
+    /// - The `SwiftName` of a method has as many argument labels as the
+    ///   selector has arguments, see [`DiagnosticKind::SwiftNameArity`].
+    /// - `Parameters` of a method only refer to arguments of the selector.
+    /// - `Parameters` of a function only refer to arguments named in its
+    ///   `SwiftName`, if it has argument labels.
+    /// - The legacy `Nullability` list of a method has an entry for each
+    ///   argument, and agrees with `Parameters`.
+    /// - A tag doesn't combine `EnumKind` with `EnumExtensibility` or
+    ///   `FlagEnum`.
+    /// - A property doesn't have entries both with and without a
+    ///   `PropertyKind`.
+    ///
+    /// The diagnostics are sorted by path.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        self.data.validate("", &mut diagnostics);
+        for (version, data) in &self.swift_versions {
+            data.validate(&format!("SwiftVersions[{version}]."), &mut diagnostics);
+        }
+        diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+        diagnostics
+    }
+}
+
+impl Data {
+    fn validate(&self, prefix: &str, diagnostics: &mut Vec<Diagnostic>) {
+        for (name, class) in &self.classes {
+            let path = format!("{prefix}Classes[{name}]");
+            validate_methods(&path, &class.methods, diagnostics);
+            validate_properties(&path, &class.properties, diagnostics);
+        }
+        for (name, protocol) in &self.protocols {
+            let path = format!("{prefix}Protocols[{name}]");
+            validate_methods(&path, &protocol.methods, diagnostics);
+            validate_properties(&path, &protocol.properties, diagnostics);
+        }
+        for (name, tag) in &self.tags {
+            if tag.enum_kind.is_some()
+                && (tag.enum_extensibility.is_some() || tag.flag_enum.is_some())
+            {
+                diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::EnumKindConflict,
+                    path: format!("{prefix}Tags[{name}]"),
+                });
+            }
+            for (method_name, method) in &tag.methods {
+                validate_function(
+                    &format!("{prefix}Tags[{name}].Methods[{method_name}]"),
+                    method,
+                    diagnostics,
+                );
+            }
+        }
+        for (name, function) in &self.functions {
+            validate_function(&format!("{prefix}Functions[{name}]"), function, diagnostics);
+        }
+        for (name, namespace) in &self.namespaces {
+            namespace
+                .data
+                .validate(&format!("{prefix}Namespaces[{name}]."), diagnostics);
+        }
+    }
+}
+
+fn method_path(parent: &str, key: &MethodKey) -> String {
+    let sign = match key.kind {
+        Kind::Instance => '-',
+        Kind::Class => '+',
+    };
+    format!("{parent}.Methods[{sign}{}]", key.selector)
+}
+
+fn validate_methods(
+    parent: &str,
+    methods: &Map<MethodKey, Method>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (key, method) in methods {
+        let path = method_path(parent, key);
+        let num_args = key.selector.num_args();
+        if let Some(Ok(swift_name)) = method.general.parsed_swift_name() {
+            // Accessor names are not allowed on methods, and are handled by
+            // clang separately.
+            if let (None, Some(found)) = (swift_name.accessor, swift_name.num_args()) {
+                if !swift_name_arity_matches(&swift_name, found, key, method) {
+                    diagnostics.push(Diagnostic {
+                        kind: DiagnosticKind::SwiftNameArity {
+                            expected: num_args,
+                            found,
+                        },
+                        path: path.clone(),
+                    });
+                }
+            }
+        }
+        validate_parameters(&path, &method.parameters, num_args, diagnostics);
+        for kind in method.resolved_nullability(&key.selector).problems {
+            let path = match kind {
+                DiagnosticKind::NullabilityConflict { position, .. } => {
+                    format!("{path}.Parameters[{position}]")
+                }
+                _ => path.clone(),
+            };
+            diagnostics.push(Diagnostic { kind, path });
+        }
+    }
+}
+
+/// Whether a `SwiftName` with `found` argument labels fits the selector of
+/// a method, following clang's rules.
+fn swift_name_arity_matches(
+    swift_name: &SwiftName,
+    found: usize,
+    key: &MethodKey,
+    method: &Method,
+) -> bool {
+    let expected = key.selector.num_args();
+    if found > expected {
+        // A method without arguments can be imported as an initializer with
+        // a single label, e.g. `+defaultWidget` as `init(defaultWidget:)`.
+        let arguments = swift_name.arguments.as_deref().unwrap_or_default();
+        return expected == 0
+            && swift_name.kind() == SwiftNameKind::Initializer
+            && matches!(arguments, [Some(_)]);
+    }
+    // Out-parameters, e.g. an `NSError **` that becomes a Swift error, are
+    // left out of the Swift name. Like clang, count any argument that may be
+    // one rather than warn.
+    let out_parameters = key
+        .selector
+        .pieces()
+        .enumerate()
+        .take(expected)
+        .filter(|&(index, piece)| {
+            let parameter = Position::try_from(index)
+                .ok()
+                .and_then(|position| method.parameters.get(&position));
+            may_be_out_parameter(piece, parameter)
+        })
+        .count();
+    found + out_parameters >= expected
+}
+
+/// Whether the argument for the selector piece `piece` may be a pointer to
+/// a non-`const` value.
+///
+/// Without a type override, the type is only known from the header, so this
+/// guesses from the name and attributes of the argument.
+fn may_be_out_parameter(piece: &str, parameter: Option<&Parameter>) -> bool {
+    if let Some(type_) = parameter.and_then(|parameter| parameter.type_.as_ref()) {
+        return match type_.parse::<CType>() {
+            Ok(CType {
+                kind: CTypeKind::Pointer(pointee),
+                ..
+            }) => !pointee.qualifiers.is_const,
+            Ok(_) => false,
+            Err(_) => true,
+        };
+    }
+    parameter.is_some_and(|parameter| parameter.retain_count_convention.is_some())
+        || piece.to_ascii_lowercase().ends_with("error")
+}
+
+fn validate_properties(
+    parent: &str,
+    properties: &Map<PropertyKey, Property>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for key in properties.keys() {
+        if key.kind.is_some() {
+            continue;
+        }
+        let has_kind = [Kind::Instance, Kind::Class].into_iter().any(|kind| {
+            properties.contains_key(&PropertyKey {
+                name: key.name.clone(),
+                kind: Some(kind),
+            })
+        });
+        if has_kind {
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::PropertyKindConflict,
+                path: format!("{parent}.Properties[{}]", key.name),
+            });
+        }
+    }
+}
+
+fn validate_function(path: &str, function: &Function, diagnostics: &mut Vec<Diagnostic>) {
+    // The arguments of a C function are only known from the header, but a
+    // `SwiftName` with argument labels must name all of them.
+    if let Some(num_args) = function
+        .general
+        .parsed_swift_name()
+        .and_then(Result::ok)
+        .and_then(|swift_name| swift_name.num_args())
+    {
+        validate_parameters(path, &function.parameters, num_args, diagnostics);
+    }
+}
+
+fn validate_parameters(
+    path: &str,
+    parameters: &Map<Position, Parameter>,
+    num_args: usize,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for &position in parameters.keys() {
+        if usize::from(position) >= num_args {
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::ParameterOutOfRange { position, num_args },
+                path: format!("{path}.Parameters[{position}]"),
+            });
+        }
+    }
+}
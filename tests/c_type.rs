@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use apinotes::{ApiNotes, CType, CTypeKind, ErrorKind, Ownership, TypeNullability};
+
+#[track_caller]
+fn roundtrip(source: &str, expected: &str) -> CType {
+    let ty: CType = source.parse().unwrap();
+    assert_eq!(ty.to_string(), expected);
+    assert_eq!(expected.parse::<CType>().unwrap(), ty);
+    ty
+}
+
+#[test]
+fn printing() {
+    roundtrip("int", "int");
+    roundtrip("unsigned  long long", "unsigned long long");
+    roundtrip("int const*", "const int *");
+    roundtrip("struct foo **", "struct foo **");
+    roundtrip("char * const * _Nullable", "char * const * _Nullable");
+    roundtrip(
+        "id<NSCopying, NSCoding> _Nonnull",
+        "id<NSCopying, NSCoding> _Nonnull",
+    );
+    roundtrip(
+        "NSDictionary<NSString*,NSArray<NSNumber*>*>*",
+        "NSDictionary<NSString *, NSArray<NSNumber *> *> *",
+    );
+    roundtrip("int [2]", "int [2]");
+    roundtrip("int *[2][3]", "int *[2][3]");
+    roundtrip("int (*)[]", "int (*)[]");
+    roundtrip("void (*)()", "void (*)(void)");
+    roundtrip(
+        "int (* _Nonnull)(const char *fmt, ...)",
+        "int (* _Nonnull)(const char *, ...)",
+    );
+    roundtrip(
+        "void (^ _Nullable)(BOOL finished, NSError * _Nullable error)",
+        "void (^ _Nullable)(BOOL, NSError * _Nullable)",
+    );
+    roundtrip(
+        "void (*(*)(int))(void (^)(void))",
+        "void (*(*)(int))(void (^)(void))",
+    );
+}
+
+#[test]
+fn structure() {
+    let ty = roundtrip("void (^ _Nonnull)(int)", "void (^ _Nonnull)(int)");
+    assert_eq!(ty.nullability, Some(TypeNullability::Nonnull));
+    let CTypeKind::Block(block) = &ty.kind else {
+        panic!("expected a block: {ty:?}");
+    };
+    assert_eq!(*block.result, CType::named("void"));
+    assert_eq!(block.parameters, [CType::named("int")]);
+    assert!(!block.variadic);
+
+    let ty = roundtrip("int *[2]", "int *[2]");
+    let CTypeKind::Array { element, size } = &ty.kind else {
+        panic!("expected an array: {ty:?}");
+    };
+    assert_eq!(**element, CType::pointer(CType::named("int")));
+    assert_eq!(*size, Some(2));
+
+    let ty: CType = "const int * restrict".parse().unwrap();
+    assert!(ty.qualifiers.is_restrict);
+    let CTypeKind::Pointer(pointee) = &ty.kind else {
+        panic!("expected a pointer: {ty:?}");
+    };
+    assert!(pointee.qualifiers.is_const);
+}
+
+#[test]
+fn objc_qualifiers() {
+    let ty = roundtrip("__kindof NSView *", "__kindof NSView *");
+    let CTypeKind::Pointer(pointee) = &ty.kind else {
+        panic!("expected a pointer: {ty:?}");
+    };
+    assert!(pointee.qualifiers.is_kindof);
+
+    let ty = roundtrip("NSError * __autoreleasing *", "NSError * __autoreleasing *");
+    let CTypeKind::Pointer(pointee) = &ty.kind else {
+        panic!("expected a pointer: {ty:?}");
+    };
+    assert_eq!(pointee.qualifiers.ownership, Some(Ownership::Autoreleasing));
+    roundtrip("__strong id", "__strong id");
+    roundtrip("__weak NSObject *", "__weak NSObject *");
+    roundtrip("id __unsafe_unretained", "__unsafe_unretained id");
+
+    let ty = roundtrip("NSString * __nullable", "NSString * _Nullable");
+    assert_eq!(ty.nullability, Some(TypeNullability::Nullable));
+    roundtrip("NSString * __nonnull", "NSString * _Nonnull");
+    roundtrip("id __null_unspecified", "id _Null_unspecified");
+}
+
+#[test]
+fn invalid() {
+    for source in [
+        "",
+        "*",
+        "int x",
+        "int *)",
+        "int (*",
+        "int [x]",
+        "void ^(int)",
+        "int (^)",
+        "NSArray<NSString *",
+        "struct",
+        "int #",
+        "_Atomic(int)",
+        "_Atomic int",
+        &"A<".repeat(200_000),
+        &format!("int {}", "*".repeat(200_000)),
+        &format!("int {}*{}", "(".repeat(200_000), ")".repeat(200_000)),
+        &format!("int {}", "[1]".repeat(200_000)),
+    ] {
+        let err = source.parse::<CType>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue, "{source}");
+        assert!(
+            err.to_string()
+                .contains(&format!("invalid C type `{source}`")),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("clang_tests");
+    for entry in dir.read_dir().unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension() != Some("apinotes".as_ref()) {
+            continue;
+        }
+        let Ok(notes) = ApiNotes::from_path(&path) else {
+            continue;
+        };
+        let yaml = serde_yaml::to_value(&notes).unwrap();
+        let mut types = Vec::new();
+        collect_types(&yaml, &mut types);
+        for ty in types {
+            let parsed: CType = ty
+                .parse()
+                .unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+            assert_eq!(parsed.to_string().parse::<CType>().unwrap(), parsed);
+        }
+    }
+}
+
+fn collect_types(value: &serde_yaml::Value, types: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                match (key.as_str(), value.as_str()) {
+                    (Some("Type" | "ResultType"), Some(ty)) => types.push(ty.into()),
+                    _ => collect_types(value, types),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                collect_types(item, types);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,100 @@
+use apinotes::{ApiNotes, Class, DiagnosticKind, Kind, Nullability, PropertyKey};
+
+const SOURCE: &str = "\
+Name: SomeKit
+Classes:
+  - Name: Box
+    SwiftImportAsNonGeneric: true
+    SwiftObjCMembers: true
+    SwiftConformsTo: Swift.Sendable
+    SwiftBridge: SwiftBox
+Protocols:
+  - Name: Delegate
+    SwiftObjCMembers: false
+    SwiftConformsTo: Swift.Sendable
+";
+
+#[test]
+fn swift_attributes() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let class = &notes.data.classes["Box"];
+    assert_eq!(class.swift_import_as_non_generic, Some(true));
+    assert_eq!(class.swift_objc_members, Some(true));
+    assert_eq!(class.swift_conforms_to.as_deref(), Some("Swift.Sendable"));
+
+    let protocol = &notes.data.protocols["Delegate"];
+    assert_eq!(protocol.swift_import_as_non_generic, None);
+    assert_eq!(protocol.swift_objc_members, Some(false));
+    assert_eq!(
+        protocol.swift_conforms_to.as_deref(),
+        Some("Swift.Sendable")
+    );
+
+    let formatted = notes.to_canonical_string().unwrap();
+    assert!(formatted.contains(
+        "    SwiftBridge: SwiftBox\n    SwiftConformsTo: Swift.Sendable\n    \
+         SwiftImportAsNonGeneric: true\n    SwiftObjCMembers: true\n"
+    ));
+    assert_eq!(formatted.parse::<ApiNotes>().unwrap(), notes);
+}
+
+const PROPERTIES: &str = "\
+Name: SomeKit
+Classes:
+  - Name: A
+    Properties:
+      - Name: shared
+        Nullability: N
+      - Name: shared
+        PropertyKind: Class
+        Nullability: O
+      - Name: value
+        PropertyKind: Instance
+        SwiftPrivate: true
+";
+
+#[test]
+fn properties() {
+    let notes: ApiNotes = PROPERTIES.parse().unwrap();
+    let mut class = notes.data.classes["A"].clone();
+    let nullability = |class: &Class, name, kind| {
+        class
+            .property(name, kind)
+            .and_then(|property| property.nullability.clone())
+    };
+    assert_eq!(
+        nullability(&class, "shared", Kind::Instance),
+        Some(Nullability::Nonnull)
+    );
+    assert_eq!(
+        nullability(&class, "shared", Kind::Class),
+        Some(Nullability::Optional)
+    );
+    assert!(class.property("value", Kind::Instance).is_some());
+    assert!(class.property("value", Kind::Class).is_none());
+
+    let conflicts = class.expand_properties();
+    assert_eq!(
+        conflicts,
+        [PropertyKey {
+            name: "shared".into(),
+            kind: Some(Kind::Class)
+        }]
+    );
+    assert_eq!(class.properties.len(), 3);
+    assert!(class.properties.keys().all(|key| key.kind.is_some()));
+    assert_eq!(
+        nullability(&class, "shared", Kind::Instance),
+        Some(Nullability::Nonnull)
+    );
+    assert_eq!(
+        nullability(&class, "shared", Kind::Class),
+        Some(Nullability::Optional)
+    );
+    assert_eq!(class.expand_properties(), []);
+
+    let diagnostics = notes.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::PropertyKindConflict);
+    assert_eq!(diagnostics[0].path, "Classes[A].Properties[shared]");
+}
@@ -0,0 +1,91 @@
+use apinotes::{ApiNotes, ChangeKind};
+
+const OLD: &str = "\
+Name: SomeKit
+Classes:
+  - Name: A
+    Methods:
+      - Selector: 'initWithA:'
+        MethodKind: Instance
+        Parameters:
+          - Position: 0
+            Nullability: N
+      - Selector: 'removed'
+        MethodKind: Class
+    Properties:
+      - Name: p
+        Nullability: O
+SwiftVersions:
+  - Version: 3
+    Functions:
+      - Name: f
+        SwiftPrivate: true
+";
+
+const NEW: &str = "\
+Name: SomeKit
+Classes:
+  - Name: A
+    Methods:
+      - Selector: 'initWithA:'
+        MethodKind: Instance
+        Nullability: [N]
+        Parameters:
+          - Position: 0
+            Nullability: O
+    Properties:
+      - Name: p
+        PropertyKind: Instance
+        Nullability: O
+  - Name: B
+SwiftVersions:
+  - Version: 3
+    Functions:
+      - Name: f
+";
+
+#[test]
+fn changes() {
+    let old: ApiNotes = OLD.parse().unwrap();
+    let new: ApiNotes = NEW.parse().unwrap();
+    let changes: Vec<_> = old.diff(&new).iter().map(ToString::to_string).collect();
+    assert_eq!(
+        changes,
+        [
+            "~ Classes[A].Methods[-initWithA:].Parameters[0].Nullability: Nonnull -> Optional",
+            "+ Classes[A].Methods[-initWithA:].Nullability: [Nonnull]",
+            "- Classes[A].Methods[+removed]",
+            "- Classes[A].Properties[p]",
+            "+ Classes[A].Properties[-p]",
+            "+ Classes[B]",
+            "- SwiftVersions[3.0].Functions[f].SwiftPrivate: true",
+        ]
+    );
+
+    let reverse = new.diff(&old);
+    assert_eq!(reverse.len(), changes.len());
+    assert!(reverse
+        .iter()
+        .any(|change| change.kind == ChangeKind::Removed && change.path == "Classes[B]"));
+
+    assert_eq!(old.diff(&old), []);
+}
+
+#[test]
+fn empty_list() {
+    let without: ApiNotes = "Name: A\nClasses:\n  - Name: B\n    Methods:\n      - Selector: a\n        MethodKind: Instance\n".parse().unwrap();
+    let with: ApiNotes = "Name: A\nClasses:\n  - Name: B\n    Methods:\n      - Selector: a\n        MethodKind: Instance\n        Nullability: []\n".parse().unwrap();
+    assert_ne!(without, with);
+    let changes: Vec<_> = without
+        .diff(&with)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(changes, ["+ Classes[B].Methods[-a].Nullability: []"]);
+    let changes: Vec<_> = with
+        .diff(&without)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(changes, ["- Classes[B].Methods[-a].Nullability: []"]);
+}
@@ -0,0 +1,182 @@
+use apinotes::{Document, Kind, MethodKey};
+
+const SOURCE: &str = "\
+# Notes for SomeKit.
+Name: SomeKit
+Classes:
+  - Name: A # The main class.
+    Methods:
+      - Selector:        \"transform:integer:\"
+        MethodKind:      Instance
+        # Keep these in sync with the header.
+        Nullability:      [ N, S ]
+      - Selector: \"init\"
+        MethodKind: Instance
+Functions:
+  - Name: foo
+    Parameters: []
+";
+
+#[test]
+fn unchanged_roundtrip() {
+    for name in [
+        "Simple.apinotes",
+        "SimpleKit.apinotes",
+        "SomeKit.apinotes",
+        "SomeKit_private.apinotes",
+    ] {
+        let path = format!("{}/src/clang_tests/{name}", env!("CARGO_MANIFEST_DIR"));
+        let source = std::fs::read_to_string(path).unwrap();
+        let doc = Document::parse(&source).unwrap();
+        assert_eq!(doc.as_str(), source);
+        assert_eq!(doc.to_api_notes().unwrap(), source.parse().unwrap());
+    }
+}
+
+#[test]
+fn edit_method() {
+    let mut doc = Document::parse(SOURCE).unwrap();
+    let class = doc.find(&doc.root(), "Classes", &[("Name", "A")]).unwrap();
+    let method = doc
+        .find(
+            &class,
+            "Methods",
+            &[
+                ("Selector", "transform:integer:"),
+                ("MethodKind", "Instance"),
+            ],
+        )
+        .unwrap();
+
+    doc.set(&method, "SwiftName", "transform(_:integer:)")
+        .unwrap();
+    doc.set(&method, "MethodKind", "Instance").unwrap();
+    doc.push(
+        &method,
+        "Parameters",
+        &[("Position", 0.into()), ("NoEscape", true.into())],
+    )
+    .unwrap();
+
+    let function = doc
+        .find(&doc.root(), "Functions", &[("Name", "foo")])
+        .unwrap();
+    doc.push(&function, "Parameters", &[("Position", 0.into())])
+        .unwrap();
+
+    let expected = "\
+# Notes for SomeKit.
+Name: SomeKit
+Classes:
+  - Name: A # The main class.
+    Methods:
+      - Selector:        \"transform:integer:\"
+        MethodKind:      Instance
+        # Keep these in sync with the header.
+        Nullability:      [ N, S ]
+        SwiftName: transform(_:integer:)
+        Parameters:
+          - Position: 0
+            NoEscape: true
+      - Selector: \"init\"
+        MethodKind: Instance
+Functions:
+  - Name: foo
+    Parameters:
+      - Position: 0
+";
+    assert_eq!(doc.as_str(), expected);
+
+    let notes = doc.to_api_notes().unwrap();
+    let key = MethodKey {
+        selector: "transform:integer:".parse().unwrap(),
+        kind: Kind::Instance,
+    };
+    let method = &notes.data.classes["A"].methods[&key];
+    assert_eq!(
+        method.general.swift_name.as_ref().map(ToString::to_string),
+        Some("transform(_:integer:)".into())
+    );
+    assert!(method.parameters[&0].no_escape);
+}
+
+#[test]
+fn quoting() {
+    let mut doc = Document::parse(SOURCE).unwrap();
+    let function = doc
+        .find(&doc.root(), "Functions", &[("Name", "foo")])
+        .unwrap();
+    for value in [
+        "null", "~", "true", "yes", "0x1", "1.5", "a: b", "", "a\u{1}b", "a\rb", "\tb\n",
+    ] {
+        doc.set(&function, "AvailabilityMsg", value).unwrap();
+        let at = doc.get(&function, "AvailabilityMsg").unwrap();
+        assert_eq!(doc.scalar(&at), Some(value));
+        assert_ne!(
+            doc.as_str().lines().last(),
+            Some(&*format!("    AvailabilityMsg: {value}"))
+        );
+        let notes = doc.to_api_notes().unwrap();
+        let message = &notes.data.functions["foo"].general.availability_msg;
+        assert_eq!(message.as_deref(), Some(value));
+        let reparsed = Document::parse(doc.as_str()).unwrap();
+        let function = reparsed
+            .find(&reparsed.root(), "Functions", &[("Name", "foo")])
+            .unwrap();
+        let at = reparsed.get(&function, "AvailabilityMsg").unwrap();
+        assert_eq!(reparsed.scalar(&at), Some(value));
+    }
+
+    let escaped = Document::parse("Name: \"\\x41\\u00e9\\U0001F600\"\n").unwrap();
+    let name = escaped.get(&escaped.root(), "Name").unwrap();
+    assert_eq!(escaped.scalar(&name), Some("A\u{e9}\u{1F600}"));
+    assert!(Document::parse("Name: \"\\x4\"\n").is_err());
+    assert!(Document::parse("Name: \"\\uD800\"\n").is_err());
+
+    doc.set(&function, "SwiftPrivate", true).unwrap();
+    assert!(doc.as_str().ends_with("SwiftPrivate: true\n"));
+    assert!(
+        doc.to_api_notes().unwrap().data.functions["foo"]
+            .general
+            .swift_private
+    );
+    assert!(doc.set(&function, "AvailabilityMsg", vec!["a"]).is_err());
+}
+
+#[test]
+fn remove_entry() {
+    let mut doc = Document::parse(SOURCE).unwrap();
+    let class = doc.find(&doc.root(), "Classes", &[("Name", "A")]).unwrap();
+    let method = doc
+        .find(&class, "Methods", &[("Selector", "init")])
+        .unwrap();
+    assert!(doc.remove(&method, "MethodKind").unwrap());
+    assert!(!doc.remove(&method, "MethodKind").unwrap());
+    assert!(doc.remove(&method, "Selector").is_err());
+    assert!(!doc.as_str().contains("MethodKind: Instance\n"));
+    assert!(doc.as_str().contains("MethodKind:      Instance\n"));
+}
+
+#[test]
+fn invalid() {
+    assert!(Document::parse("Name: A\n  Classes: []\n").is_err());
+    assert!(Document::parse("Name: 'A\n").is_err());
+    assert!(Document::parse("Name: A\nName: B\n").is_err());
+}
+
+#[test]
+fn crlf() {
+    let mut doc = Document::parse("Name: Foo\r\nFunctions:\r\n  - Name: f\r\n").unwrap();
+    let function = doc
+        .find(&doc.root(), "Functions", &[("Name", "f")])
+        .unwrap();
+    doc.set(&function, "SwiftName", "g()").unwrap();
+    doc.push(&doc.root(), "Globals", &[("Name", "x".into())])
+        .unwrap();
+    doc.push(&function, "Parameters", &[("Position", 0.into())])
+        .unwrap();
+    assert_eq!(
+        doc.as_str(),
+        "Name: Foo\r\nFunctions:\r\n  - Name: f\r\n    SwiftName: g()\r\n    Parameters:\r\n      - Position: 0\r\nGlobals:\r\n  - Name: x\r\n"
+    );
+}
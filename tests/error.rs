@@ -0,0 +1,120 @@
+use std::error::Error as _;
+
+use apinotes::{ApiNotes, ErrorKind};
+
+fn error(source: &str) -> apinotes::Error {
+    source.parse::<ApiNotes>().unwrap_err()
+}
+
+#[test]
+fn kinds_and_marks() {
+    let err = error("Name: A\nClasses:\n  - Name: B\n  - Name: B\n");
+    assert_eq!(err.kind(), ErrorKind::DuplicateEntry);
+    assert_eq!(err.mark().unwrap().line, 4);
+
+    let err = error(
+        "Name: A\nClasses:\n  - Name: B\n    Methods:\n      - Selector: a\n        MethodKind: Foo\n",
+    );
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 5);
+
+    let err = error("Name: A\nFunctions:\n  - Parameters: []\n");
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+    assert_eq!(err.mark().unwrap().line, 3);
+
+    let err = error("Classes: []\n");
+    assert_eq!(err.kind(), ErrorKind::MissingField);
+
+    let err = error("Name: A\nClasses: 3\n");
+    assert_eq!(err.kind(), ErrorKind::InvalidType);
+    assert_eq!(err.mark().unwrap().line, 2);
+
+    let err = error("Name: A\nClasses:\n  - Name: B\n   x: [\n");
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    assert_eq!(err.mark().unwrap().line, 4);
+    assert!(err.source().unwrap().is::<serde_yaml::Error>());
+
+    // The kind doesn't depend on the message.
+    let err = error("Name: A\nClasses:\n  - Name: B\n    Availability: duplicate entry\n");
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+}
+
+#[test]
+fn path() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let err = ApiNotes::from_path(&path).unwrap_err();
+    assert_eq!(err.path(), Some(&*path));
+    assert!(err.to_string().starts_with(&*path.to_string_lossy()));
+}
+
+#[test]
+fn io() {
+    let path = std::path::Path::new("does/not/exist.apinotes");
+    let err = ApiNotes::from_path(path).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io(std::io::ErrorKind::NotFound));
+    assert_eq!(err.path(), Some(path));
+    let source = err.source().unwrap().downcast_ref::<std::io::Error>();
+    assert_eq!(source.unwrap().kind(), std::io::ErrorKind::NotFound);
+
+    let notes = ApiNotes::from_reader(&b"Name: A\n"[..]).unwrap();
+    assert_eq!(notes.name, "A");
+}
+
+#[test]
+fn invalid_utf8() {
+    let err = ApiNotes::from_slice(b"Name: A\nClasses:\n  - Name: \xc3\xa9\xff\n").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+    let mark = err.mark().unwrap();
+    assert_eq!((mark.index, mark.line, mark.column), (29, 3, 12));
+    assert!(err.source().unwrap().is::<std::str::Utf8Error>());
+}
+
+#[test]
+fn unknown_availability() {
+    let err = error("Name: A\nClasses:\n  - Name: B\n    Availability: sometimes\n");
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 4);
+
+    // Also in entries that are nested, and for attributes after the general
+    // ones.
+    let err = error(
+        "Name: A\nClasses:\n  - Name: B\n    Methods:\n      - Selector: a\n        MethodKind: Instance\n        SwiftPrivate: true\n        Availability: bogus\n",
+    );
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 8);
+    let err = error("Name: A\nTags:\n  - Name: B\n    SwiftName: C\n    EnumKind: bogus\n");
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 5);
+
+    let notes: ApiNotes = "Name: A\nClasses:\n  - Name: B\n    Availability: NonSwift\n"
+        .parse()
+        .unwrap();
+    let general = &notes.data.classes["B"].general;
+    assert_eq!(general.availability, Some(apinotes::Availability::NonSwift));
+    assert!(general.is_unavailable_in_swift());
+}
+
+#[test]
+fn invalid_selector() {
+    let err = error(
+        "Name: A\nClasses:\n  - Name: B\n    Methods:\n      - Selector: 'a::'\n        MethodKind: Instance\n",
+    );
+    assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    assert_eq!(err.mark().unwrap().line, 5);
+}
+
+#[test]
+fn swift_name() {
+    // Like in clang, names that can't be parsed are still read.
+    let notes: ApiNotes = "Name: A\nFunctions:\n  - Name: f\n    SwiftName: 'f(a)'\n"
+        .parse()
+        .unwrap();
+    let general = &notes.data.functions["f"].general;
+    assert_eq!(general.swift_name.as_deref(), Some("f(a)"));
+    let err = general.parsed_swift_name().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    assert_eq!(
+        notes.to_string().unwrap(),
+        "Name: A\nFunctions:\n- Name: f\n  SwiftName: f(a)\n"
+    );
+}
@@ -0,0 +1,74 @@
+use apinotes::ApiNotes;
+
+const SOURCE: &str = r#"
+SwiftVersions:
+  - Version: 4
+    Functions:
+      - Name: b
+  - Version: 3.0
+    Functions:
+      - {Name: a, SwiftPrivate: true}
+Functions:
+  - Name: 'no'
+    Parameters:
+      - Position: 1
+        Nullability: O
+        Type: 'int [2]'
+      - Position: 0
+  - Name: c
+    SwiftName: c(_:_:)
+    Availability: nonswift
+    AvailabilityMsg: 'say "hi"'
+Name: SomeKit
+Classes:
+  - Name: B
+    Methods:
+      - Selector: foo
+        MethodKind: Class
+        Nullability: [N, O]
+      - Selector: foo
+        MethodKind: Instance
+  - Name: A
+"#;
+
+const EXPECTED: &str = r#"Name: SomeKit
+Classes:
+  - Name: A
+  - Name: B
+    Methods:
+      - Selector: "foo"
+        MethodKind: Instance
+      - Selector: "foo"
+        MethodKind: Class
+        Nullability: [Nonnull, Optional]
+Functions:
+  - Name: c
+    Availability: nonswift
+    AvailabilityMsg: "say \"hi\""
+    SwiftName: "c(_:_:)"
+  - Name: "no"
+    Parameters:
+      - Position: 0
+      - Position: 1
+        Nullability: Optional
+        Type: "int [2]"
+SwiftVersions:
+  - Version: 3.0
+    Functions:
+      - Name: a
+        SwiftPrivate: true
+  - Version: 4.0
+    Functions:
+      - Name: b
+"#;
+
+#[test]
+fn canonical() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let formatted = notes.to_canonical_string().unwrap();
+    assert_eq!(formatted, EXPECTED);
+
+    assert!(!ApiNotes::is_canonical(SOURCE).unwrap());
+    assert!(ApiNotes::is_canonical(EXPECTED).unwrap());
+    assert_eq!(formatted.parse::<ApiNotes>().unwrap(), notes);
+}
@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use apinotes::{ApiNotes, ConflictPolicy, Document, MergeReport, MethodKey};
+
+fn fixture(name: &str) -> ApiNotes {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("clang_tests")
+        .join(name);
+    ApiNotes::from_path(&path).unwrap()
+}
+
+#[test]
+fn public_and_private() {
+    let mut notes = fixture("SomeKit.apinotes");
+    let private = fixture("SomeKit_private.apinotes");
+    let report = notes.merge(&private, ConflictPolicy::Error);
+    assert_eq!(report, MergeReport::default());
+
+    let class = &notes.data.classes["A"];
+    let key = |selector: &str| MethodKey {
+        selector: selector.parse().unwrap(),
+        kind: apinotes::Kind::Instance,
+    };
+    assert!(class.methods.contains_key(&key("transform:")));
+    assert!(class.methods.contains_key(&key("privateTransform:input:")));
+    assert!(notes.data.protocols.contains_key("InternalProtocol"));
+    assert!(notes.data.globals.contains_key("global_int_ptr"));
+}
+
+const LEFT: &str = "\
+Name: SomeKit
+Functions:
+  - Name: f
+    SwiftName: 'f(a:)'
+    Parameters:
+      - Position: 0
+        Nullability: N
+SwiftVersions:
+  - Version: 3
+    Globals:
+      - Name: g
+";
+
+const RIGHT: &str = "\
+Name: SomeKit
+Functions:
+  - Name: f
+    SwiftName: 'f(b:)'
+    Availability: none
+    Parameters:
+      - Position: 0
+        Nullability: N
+      - Position: 1
+SwiftVersions:
+  - Version: 3.0
+    Globals:
+      - Name: g
+        SwiftPrivate: true
+";
+
+#[test]
+fn conflicts() {
+    let left: ApiNotes = LEFT.parse().unwrap();
+    let right: ApiNotes = RIGHT.parse().unwrap();
+
+    let mut notes = left.clone();
+    let report = notes.merge(&right, ConflictPolicy::Error);
+    assert!(report.unresolved);
+    assert_eq!(notes, left);
+    let conflicts = report.conflicts;
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].to_string(),
+        "Functions[f].SwiftName: conflicting values `f(a:)` and `f(b:)`"
+    );
+
+    let left_doc = Document::parse(LEFT).unwrap();
+    let right_doc = Document::parse(RIGHT).unwrap();
+    assert_eq!(left_doc.locate(&conflicts[0].path).unwrap().line, 4);
+    assert_eq!(right_doc.locate(&conflicts[0].path).unwrap().line, 4);
+    assert_eq!(
+        right_doc
+            .locate("SwiftVersions[3.0].Globals[g]")
+            .unwrap()
+            .line,
+        13
+    );
+    assert_eq!(right_doc.locate("Functions[g]"), None);
+
+    let mut notes = left.clone();
+    let report = notes.merge(&right, ConflictPolicy::PreferLeft);
+    assert!(!report.unresolved);
+    assert_eq!(report.conflicts.len(), 1);
+    let function = &notes.data.functions["f"];
+    assert_eq!(function.general.swift_name.as_deref().unwrap(), "f(a:)");
+    assert!(function.general.availability.is_some());
+    assert_eq!(function.parameters.len(), 2);
+    assert!(
+        notes.swift_versions[&apinotes::Version::new(3, 0, 0)].globals["g"]
+            .general
+            .swift_private
+    );
+
+    let mut notes = left;
+    assert!(!notes.merge(&right, ConflictPolicy::PreferRight).unresolved);
+    let function = &notes.data.functions["f"];
+    assert_eq!(function.general.swift_name.as_deref().unwrap(), "f(b:)");
+}
+
+#[test]
+fn files() {
+    let dir = std::env::temp_dir().join(format!("apinotes-merge-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let left = dir.join("Left.apinotes");
+    let right = dir.join("Right.apinotes");
+    std::fs::write(&left, LEFT).unwrap();
+    std::fs::write(&right, RIGHT).unwrap();
+
+    let (notes, report) = ApiNotes::merge_files(&[&left, &right], ConflictPolicy::Error).unwrap();
+    assert!(report.unresolved);
+    assert_eq!(notes, LEFT.parse().unwrap());
+    let conflict = &report.conflicts[0];
+    let left_source = conflict.left_source.as_ref().unwrap();
+    assert_eq!(left_source.path, left);
+    assert_eq!((left_source.mark.line, left_source.mark.column), (4, 5));
+    assert_eq!(
+        conflict.to_string(),
+        format!(
+            "Functions[f].SwiftName: conflicting values `f(a:)` ({}:4:5) and `f(b:)` ({}:4:5)",
+            left.display(),
+            right.display()
+        )
+    );
+
+    let (notes, report) =
+        ApiNotes::merge_files(&[&left, &right], ConflictPolicy::PreferRight).unwrap();
+    assert!(!report.unresolved);
+    assert_eq!(
+        notes.data.functions["f"]
+            .general
+            .swift_name
+            .clone()
+            .unwrap(),
+        "f(b:)"
+    );
+
+    let missing = dir.join("Missing.apinotes");
+    let err = ApiNotes::merge_files(&[&left, &missing], ConflictPolicy::Error).unwrap_err();
+    assert_eq!(err.path(), Some(&*missing));
+    assert!(ApiNotes::merge_files::<&str>(&[], ConflictPolicy::Error).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
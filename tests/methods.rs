@@ -0,0 +1,88 @@
+use apinotes::{ApiNotes, DiagnosticKind, Nullability};
+
+const NULLABILITY: &str = "\
+Name: A
+Classes:
+  - Name: B
+    Methods:
+      - Selector: 'a:b:c:'
+        MethodKind: Instance
+        Nullability: [O, N]
+        Parameters:
+          - Position: 0
+            Nullability: N
+          - Position: 1
+            Nullability: N
+      - Selector: 'd:'
+        MethodKind: Class
+        Parameters:
+          - Position: 0
+            Nullability: O
+      - Selector: 'e:'
+        MethodKind: Class
+        Nullability: []
+";
+
+#[test]
+fn nullability() {
+    let notes: ApiNotes = NULLABILITY.parse().unwrap();
+    let class = &notes.data.classes["B"];
+    let (key, method) = class
+        .methods
+        .iter()
+        .find(|(key, _)| key.selector.as_str() == "a:b:c:")
+        .unwrap();
+    let resolved = method.resolved_nullability(&key.selector);
+    assert_eq!(resolved.result, Some(Nullability::Nonnull));
+    assert_eq!(
+        resolved.parameters,
+        [
+            Some(Nullability::Nonnull),
+            Some(Nullability::Nonnull),
+            Some(Nullability::Nonnull)
+        ]
+    );
+    assert_eq!(resolved.problems.len(), 2);
+
+    let (key, method) = class
+        .methods
+        .iter()
+        .find(|(key, _)| key.selector.as_str() == "d:")
+        .unwrap();
+    let resolved = method.resolved_nullability(&key.selector);
+    assert_eq!(resolved.result, None);
+    assert_eq!(resolved.parameters, [Some(Nullability::Optional)]);
+    assert_eq!(resolved.problems, []);
+
+    // An empty list is the same as none.
+    let (key, method) = class
+        .methods
+        .iter()
+        .find(|(key, _)| key.selector.as_str() == "e:")
+        .unwrap();
+    let resolved = method.resolved_nullability(&key.selector);
+    assert_eq!(resolved.result, None);
+    assert_eq!(resolved.parameters, [None]);
+    assert_eq!(resolved.problems, []);
+
+    let diagnostics = notes.validate();
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    assert_eq!(
+        found,
+        [
+            "Classes[B].Methods[-a:b:c:]: `Nullability` has 2 entries, but the selector has 3 argument(s)",
+            "Classes[B].Methods[-a:b:c:].Parameters[0]: `Nullability` of parameter 0 is Nonnull, but Optional in the method's `Nullability` list",
+        ]
+    );
+    assert_eq!(
+        diagnostics[1].kind,
+        DiagnosticKind::NullabilityConflict {
+            position: 0,
+            legacy: Nullability::Optional,
+            parameter: Nullability::Nonnull,
+        }
+    );
+}
@@ -0,0 +1,95 @@
+use apinotes::{ApiNotes, ErrorKind, Nullability, Version};
+
+const SOURCE: &str = "\
+Name: SomeKit
+Namespaces:
+  - Name: A
+    SwiftName: AA
+    Tags:
+      - Name: T
+    Namespaces:
+      - Name: B
+        SwiftPrivate: true
+        Functions:
+          - Name: f
+            SwiftName: 'f(_:)'
+            Parameters:
+              - Position: 0
+                Nullability: N
+              - Position: 1
+        Globals:
+          - Name: g
+            Nullability: O
+SwiftVersions:
+  - Version: 4
+    Namespaces:
+      - Name: A
+        Namespaces:
+          - Name: B
+            Globals:
+              - Name: g
+                Type: 'int *'
+";
+
+#[test]
+fn parse_and_lookup() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let a = notes.data.namespace("A").unwrap();
+    assert_eq!(a.general.swift_name.as_deref().unwrap(), "AA");
+    assert!(a.data.tags.contains_key("T"));
+
+    let b = notes.data.namespace("A::B").unwrap();
+    assert!(b.general.swift_private);
+    assert_eq!(
+        b.data.functions["f"].parameters[&0].nullability,
+        Some(Nullability::Nonnull)
+    );
+    assert!(notes.data.namespace("B").is_none());
+    assert!(notes.data.namespace("A::B::C").is_none());
+
+    let formatted = notes.to_canonical_string().unwrap();
+    assert_eq!(formatted.parse::<ApiNotes>().unwrap(), notes);
+}
+
+#[test]
+fn errors() {
+    let err = "Name: A\nNamespaces:\n  - Name: B\n    Functions:\n      - Name: f\n        Availability: sometimes\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 6);
+
+    let err = "Name: A\nNamespaces:\n  - Name: B\n    SwiftPrivate: true\n    SwiftPrivate: true\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateEntry);
+
+    // Also in the attributes of the namespace itself.
+    let err = "Name: A\nNamespaces:\n  - Name: B\n    Availability: sometimes\n    Functions: []\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 4);
+}
+
+#[test]
+fn resolve_and_validate() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let data = notes.for_swift_version(Version::new(4, 0, 0));
+    let b = data.namespace("A::B").unwrap();
+    let global = &b.data.globals["g"];
+    assert_eq!(global.type_.as_deref(), Some("int *"));
+    assert_eq!(global.nullability, None);
+    assert!(b.data.functions.contains_key("f"));
+    assert!(data.namespace("A").unwrap().data.tags.contains_key("T"));
+
+    let diagnostics: Vec<_> = notes
+        .validate()
+        .into_iter()
+        .map(|diagnostic| diagnostic.path)
+        .collect();
+    assert_eq!(
+        diagnostics,
+        ["Namespaces[A].Namespaces[B].Functions[f].Parameters[1]"]
+    );
+}
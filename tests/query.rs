@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::path::Path;
+
+use apinotes::{ApiNotes, Kind, Nullability, Version};
+
+fn some_kit() -> ApiNotes {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("clang_tests")
+        .join("SomeKit.apinotes");
+    ApiNotes::from_path(&path).unwrap()
+}
+
+#[test]
+fn swift_versions() {
+    let notes = some_kit();
+    let unversioned = notes.query(None);
+    let swift3 = notes.query(Some(Version::new(3, 0, 0)));
+    let swift4 = notes.query(Some(Version::new(4, 0, 0)));
+
+    let result = |query: apinotes::Query<'_>| {
+        query
+            .method("A", "transform:integer:", Kind::Instance)
+            .unwrap()
+            .nullability_of_ret
+            .clone()
+    };
+    assert_eq!(result(unversioned), Some(Nullability::Nonnull));
+    assert_eq!(result(swift3), Some(Nullability::Optional));
+    assert_eq!(result(swift4), Some(Nullability::Nonnull));
+
+    // Members that aren't overridden come from the unversioned notes.
+    assert!(swift3.method("A", "transform:", Kind::Instance).is_some());
+    assert!(swift3
+        .property("A", "explicitNonnullInstance", Kind::Instance)
+        .is_some());
+    assert!(unversioned
+        .property("A", "explicitNonnullInstance", Kind::Instance)
+        .is_none());
+    assert!(swift3.property("A", "nonnullABoth", Kind::Class).is_some());
+
+    assert!(unversioned.method("A", "transform:", Kind::Class).is_none());
+    assert!(unversioned
+        .method("A", "not a selector", Kind::Instance)
+        .is_none());
+    assert!(unversioned
+        .method("Z", "transform:", Kind::Instance)
+        .is_none());
+    assert!(unversioned.function("global_int_fun").is_some());
+    assert!(unversioned.global("global_int_ptr").is_some());
+    assert!(unversioned.class("C").is_some());
+}
+
+const NAMESPACES: &str = "\
+Name: CxxKit
+Namespaces:
+  - Name: A
+    Namespaces:
+      - Name: B
+        Functions:
+          - Name: f
+            SwiftName: g()
+        Enumerators:
+          - Name: Red
+SwiftVersions:
+  - Version: 5
+    Namespaces:
+      - Name: A
+        Namespaces:
+          - Name: B
+            Functions:
+              - Name: f
+                SwiftPrivate: true
+";
+
+#[test]
+fn namespaces() {
+    let notes: ApiNotes = NAMESPACES.parse().unwrap();
+    let unversioned = notes.query(None);
+    let function = unversioned.function("A::B::f").unwrap();
+    assert!(function.general.swift_name.is_some());
+    assert!(unversioned.function("f").is_none());
+    assert!(unversioned.enumerator("A::B::Red").is_some());
+    assert!(unversioned.namespace("A::B").is_some());
+
+    let swift5 = notes.query(Some(Version::new(5, 0, 0)));
+    let function = swift5.function("A::B::f").unwrap();
+    assert!(function.general.swift_private);
+    assert!(function.general.swift_name.is_none());
+    assert!(swift5.enumerator("A::B::Red").is_some());
+}
+
+#[test]
+fn tag_members() {
+    let notes: ApiNotes = "\
+Name: CxxKit
+Namespaces:
+  - Name: N
+    Tags:
+      - Name: S
+        Methods:
+          - Name: f
+        Fields:
+          - Name: x
+            Nullability: N
+SwiftVersions:
+  - Version: 5
+    Namespaces:
+      - Name: N
+        Tags:
+          - Name: S
+            Methods:
+              - Name: f
+                SwiftPrivate: true
+"
+    .parse()
+    .unwrap();
+
+    let unversioned = notes.query(None);
+    assert!(
+        !unversioned
+            .tag_method("N::S", "f")
+            .unwrap()
+            .general
+            .swift_private
+    );
+    assert!(unversioned.tag_method("S", "f").is_none());
+
+    let swift5 = notes.query(Some(Version::new(5, 0, 0)));
+    assert!(
+        swift5
+            .tag_method("N::S", "f")
+            .unwrap()
+            .general
+            .swift_private
+    );
+    // Members that aren't overridden come from the unversioned notes.
+    let field = swift5.tag_field("N::S", "x").unwrap();
+    assert_eq!(field.nullability, Some(Nullability::Nonnull));
+    assert!(swift5.tag_field("N::S", "y").is_none());
+}
+
+#[test]
+fn type_overrides() {
+    let notes: ApiNotes = "\
+Name: SomeKit
+Classes:
+  - Name: A
+    Methods:
+      - Selector: 'a:'
+        MethodKind: Instance
+        NullabilityOfRet: N
+        ResultType: 'A *'
+        Parameters:
+          - Position: 0
+            Nullability: O
+            Type: 'id'
+      - Selector: b
+        MethodKind: Instance
+        NullabilityOfRet: N
+Globals:
+  - Name: g
+    Nullability: N
+    Type: 'int *'
+"
+    .parse()
+    .unwrap();
+
+    // The nullability is dropped like with `for_swift_version`.
+    let query = notes.query(None);
+    let resolved = notes.for_swift_version(Version::new(5, 0, 0));
+    let a = query.method("A", "a:", Kind::Instance).unwrap();
+    assert_eq!(a.nullability_of_ret, None);
+    assert_eq!(a.parameters.values().next().unwrap().nullability, None);
+    let global = query.global("g").unwrap();
+    assert_eq!(*global, resolved.globals["g"]);
+
+    let b = query.method("A", "b", Kind::Instance).unwrap();
+    assert!(matches!(b, Cow::Borrowed(_)));
+    assert_eq!(b.nullability_of_ret, Some(Nullability::Nonnull));
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use apinotes::{ApiNotes, Document, ErrorKind};
+
+fn some_kit() -> ApiNotes {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("clang_tests")
+        .join("SomeKit.apinotes");
+    ApiNotes::from_path(&path).unwrap()
+}
+
+fn paths(notes: &ApiNotes, path: &str) -> Vec<String> {
+    notes
+        .select(path)
+        .unwrap()
+        .into_iter()
+        .map(|selection| selection.path)
+        .collect()
+}
+
+#[test]
+fn paths_and_wildcards() {
+    let notes = some_kit();
+    assert_eq!(
+        paths(&notes, "Classes[A].Properties[nonnullAInstance]"),
+        ["Classes[A].Properties[-nonnullAInstance]"]
+    );
+    assert_eq!(
+        paths(&notes, "Classes[A].Properties[+nonnullAInstance]"),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        paths(&notes, "Classes[*].Properties[*].Type"),
+        ["Classes[OverriddenTypes].Properties[-intPropertyToMangle].Type"]
+    );
+    assert_eq!(
+        paths(
+            &notes,
+            "Classes[OverriddenTypes].Methods[*].Parameters[*].*"
+        ),
+        [
+            "Classes[OverriddenTypes].Methods[-methodToMangle:second:].Parameters[0].Position",
+            "Classes[OverriddenTypes].Methods[-methodToMangle:second:].Parameters[0].Type",
+            "Classes[OverriddenTypes].Methods[-methodToMangle:second:].Parameters[1].Position",
+            "Classes[OverriddenTypes].Methods[-methodToMangle:second:].Parameters[1].Type",
+        ]
+    );
+    assert_eq!(
+        paths(&notes, "SwiftVersions[3].Classes[A].Properties[*]"),
+        [
+            "SwiftVersions[3.0].Classes[A].Properties[-explicitNonnullInstance]",
+            "SwiftVersions[3.0].Classes[A].Properties[-explicitNullableInstance]",
+        ]
+    );
+
+    let selections = notes.select("Globals[global_int_ptr]").unwrap();
+    assert_eq!(selections.len(), 1);
+    assert_eq!(selections[0].value["Type"], "double (*)(int, int)");
+    assert_eq!(notes.select("Functions[nope]").unwrap(), []);
+}
+
+#[test]
+fn locate_selections() {
+    let source =
+        "Name: A\nClasses:\n  - Name: B\n    SwiftName: C\n  - Name: D\n    SwiftName: E\n";
+    let notes: ApiNotes = source.parse().unwrap();
+    let document = Document::parse(source).unwrap();
+    let lines: Vec<_> = notes
+        .select("Classes[*].SwiftName")
+        .unwrap()
+        .iter()
+        .map(|selection| document.locate(&selection.path).unwrap().line)
+        .collect();
+    assert_eq!(lines, [4, 6]);
+}
+
+#[test]
+fn invalid_paths() {
+    let notes = some_kit();
+    for path in ["Classes[A", "Classes..Name", "[A]", "Classes[A]x"] {
+        let err = notes.select(path).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue, "{path}");
+    }
+}
+
+#[test]
+fn canonical_order() {
+    let notes: ApiNotes = "\
+Name: A
+Classes:
+  - Name: B
+    SwiftBridge: C
+    Methods:
+      - Selector: init
+        MethodKind: Instance
+    SwiftName: D
+    Availability: nonswift
+"
+    .parse()
+    .unwrap();
+    assert_eq!(
+        paths(&notes, "Classes[B].*"),
+        [
+            "Classes[B].Name",
+            "Classes[B].Availability",
+            "Classes[B].SwiftName",
+            "Classes[B].SwiftBridge",
+            "Classes[B].Methods",
+        ]
+    );
+}
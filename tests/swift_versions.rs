@@ -0,0 +1,219 @@
+use apinotes::{ApiNotes, Kind, Nullability, Version};
+
+const SOURCE: &str = "\
+Name: SomeKit
+SwiftVersions:
+  - Version: 5.10
+    Functions:
+      - Name: a
+  - Version: 5.1
+    Functions:
+      - Name: b
+  - Version: 6
+    Functions:
+      - Name: c
+  - Version: '4.2.1'
+    Functions:
+      - Name: d
+";
+
+#[test]
+fn versions() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let mut versions: Vec<_> = notes.swift_versions.keys().copied().collect();
+    versions.sort();
+    assert_eq!(
+        versions,
+        [
+            Version::new(4, 2, 1),
+            Version::new(5, 1, 0),
+            Version::new(5, 10, 0),
+            Version::new(6, 0, 0),
+        ]
+    );
+    assert!(notes.swift_versions[&Version::new(5, 10, 0)]
+        .functions
+        .contains_key("a"));
+
+    let roundtripped: ApiNotes = notes.to_string().unwrap().parse().unwrap();
+    assert_eq!(notes, roundtripped);
+
+    assert_eq!("5".parse::<Version>().unwrap(), Version::new(5, 0, 0));
+    assert_eq!(Version::new(5, 10, 0).to_string(), "5.10");
+    assert!("5.x".parse::<Version>().is_err());
+}
+
+#[test]
+fn invalid_version() {
+    let err = "Name: A\nSwiftVersions:\n  - Version: five\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), apinotes::ErrorKind::InvalidValue);
+    assert_eq!(err.mark().unwrap().line, 3);
+
+    let err = "Name: A\nSwiftVersions:\n  - Version: 5\n  - Version: 5.0\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), apinotes::ErrorKind::DuplicateEntry);
+}
+
+#[test]
+fn kindless_property_overlay() {
+    let notes: ApiNotes = "\
+Name: A
+Classes:
+  - Name: C
+    Properties:
+      - Name: p
+        Nullability: N
+SwiftVersions:
+  - Version: 4
+    Classes:
+      - Name: C
+        Properties:
+          - Name: p
+            PropertyKind: Class
+            Nullability: O
+"
+    .parse()
+    .unwrap();
+
+    let data = notes.for_swift_version(Version::new(4, 0, 0));
+    let properties = &data.classes["C"].properties;
+    assert_eq!(properties.len(), 2);
+    for (key, property) in properties {
+        let expected = match key.kind {
+            Some(Kind::Class) => Nullability::Optional,
+            Some(Kind::Instance) => Nullability::Nonnull,
+            None => panic!("kindless property should have been split"),
+        };
+        assert_eq!(property.nullability, Some(expected));
+    }
+
+    let data = notes.for_swift_version(Version::new(5, 0, 0));
+    assert_eq!(data, notes.data);
+}
+
+#[test]
+fn other_formats() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("clang_tests")
+        .join("SomeKit.apinotes");
+    for notes in [SOURCE.parse().unwrap(), ApiNotes::from_path(&path).unwrap()] {
+        let json = serde_json::to_string(&notes).unwrap();
+        assert_eq!(serde_json::from_str::<ApiNotes>(&json).unwrap(), notes);
+
+        let value = serde_yaml::to_value(&notes).unwrap();
+        assert_eq!(serde_yaml::from_value::<ApiNotes>(value).unwrap(), notes);
+    }
+
+    // Versions are read as written when deserializing with serde directly.
+    let notes: ApiNotes = serde_yaml::from_str(SOURCE).unwrap();
+    assert_eq!(notes, SOURCE.parse().unwrap());
+    assert!(notes.swift_versions.contains_key(&Version::new(5, 10, 0)));
+
+    // Numeric positions are accepted, versions are written as strings.
+    let json = r#"{"Name": "A", "Functions": [{"Name": "f", "Parameters": [{"Position": 1}]}],
+        "SwiftVersions": [{"Version": "5", "Globals": [{"Name": "g"}]}]}"#;
+    let notes: ApiNotes = serde_json::from_str(json).unwrap();
+    assert!(notes.data.functions["f"].parameters.contains_key(&1));
+    assert!(notes.swift_versions[&Version::new(5, 0, 0)]
+        .globals
+        .contains_key("g"));
+}
+
+#[test]
+fn tag_overlay() {
+    let notes: ApiNotes = "\
+Name: A
+Tags:
+  - Name: S
+    SwiftName: T
+    Methods:
+      - Name: f
+        SwiftName: g()
+      - Name: h
+    Fields:
+      - Name: x
+        Nullability: N
+SwiftVersions:
+  - Version: 5
+    Tags:
+      - Name: S
+        SwiftPrivate: true
+        Methods:
+          - Name: h
+            SwiftPrivate: true
+"
+    .parse()
+    .unwrap();
+
+    let data = notes.for_swift_version(Version::new(5, 0, 0));
+    let tag = &data.tags["S"];
+    assert!(tag.general.swift_private);
+    assert_eq!(tag.general.swift_name, None);
+    assert!(tag.methods["f"].general.swift_name.is_some());
+    assert!(tag.methods["h"].general.swift_private);
+    assert_eq!(tag.fields["x"].nullability, Some(Nullability::Nonnull));
+}
+
+#[test]
+fn closest_version() {
+    let notes: ApiNotes = "\
+Name: A
+Functions:
+  - Name: f
+    SwiftName: old()
+  - Name: g
+    SwiftName: old()
+  - Name: h
+    SwiftName: old()
+SwiftVersions:
+  - Version: 5
+    Functions:
+      - Name: f
+        SwiftName: five()
+  - Version: 6
+    Functions:
+      - Name: f
+        SwiftName: six()
+      - Name: g
+        SwiftName: six()
+  - Version: 7
+    Functions:
+      - Name: g
+        SwiftName: seven()
+"
+    .parse()
+    .unwrap();
+
+    let swift_name = |data: &apinotes::Data, name: &str| {
+        data.functions[name].general.swift_name.clone().unwrap()
+    };
+    // Each entity comes from the smallest version that is at least the
+    // target and mentions it.
+    let data = notes.for_swift_version(Version::new(5, 9, 0));
+    assert_eq!(swift_name(&data, "f"), "six()");
+    assert_eq!(swift_name(&data, "g"), "six()");
+    assert_eq!(swift_name(&data, "h"), "old()");
+
+    let data = notes.for_swift_version(Version::new(4, 0, 0));
+    assert_eq!(swift_name(&data, "f"), "five()");
+
+    let data = notes.for_swift_version(Version::new(6, 1, 0));
+    assert_eq!(swift_name(&data, "f"), "old()");
+    assert_eq!(swift_name(&data, "g"), "seven()");
+
+    let data = notes.for_swift_version(Version::new(8, 0, 0));
+    assert_eq!(data, notes.data);
+
+    // `query` picks the same entries.
+    for version in [4, 5, 6, 7, 8].map(|major| Version::new(major, 9, 0)) {
+        let data = notes.for_swift_version(version);
+        let query = notes.query(Some(version));
+        for name in ["f", "g", "h"] {
+            assert_eq!(*query.function(name).unwrap(), data.functions[name]);
+        }
+    }
+}
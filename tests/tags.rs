@@ -0,0 +1,147 @@
+use apinotes::{
+    ApiNotes, DiagnosticKind, EnumExtensibility, ErrorKind, Nullability, SwiftImportAs,
+};
+
+const SOURCE: &str = "\
+Name: CxxKit
+Tags:
+  - Name: RefCounted
+    SwiftImportAs: reference
+    SwiftRetainOp: retainRefCounted
+    SwiftReleaseOp: releaseRefCounted
+    SwiftConformsTo: Swift.Hashable
+  - Name: UniquePtr
+    SwiftImportAs: owned
+    SwiftCopyable: false
+    SwiftDestroyOp: destroyUniquePtr
+  - Name: View
+    SwiftEscapable: false
+";
+
+#[test]
+fn cxx_attributes() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let tag = &notes.data.tags["RefCounted"];
+    assert_eq!(tag.swift_import_as, Some(SwiftImportAs::Reference));
+    assert_eq!(tag.swift_retain_op.as_deref(), Some("retainRefCounted"));
+    assert_eq!(tag.swift_release_op.as_deref(), Some("releaseRefCounted"));
+    assert_eq!(tag.swift_conforms_to.as_deref(), Some("Swift.Hashable"));
+    assert_eq!(tag.swift_copyable, None);
+
+    let tag = &notes.data.tags["UniquePtr"];
+    assert_eq!(tag.swift_import_as, Some(SwiftImportAs::Owned));
+    assert_eq!(tag.swift_copyable, Some(false));
+    assert_eq!(tag.swift_destroy_op.as_deref(), Some("destroyUniquePtr"));
+    assert_eq!(notes.data.tags["View"].swift_escapable, Some(false));
+
+    let formatted = notes.to_canonical_string().unwrap();
+    assert!(formatted.contains("    SwiftCopyable: false\n"));
+    assert_eq!(formatted.parse::<ApiNotes>().unwrap(), notes);
+}
+
+#[test]
+fn unknown_import_as() {
+    let err = "Name: A\nTags:\n  - Name: B\n    SwiftImportAs: pointer\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+    assert_eq!(err.mark().unwrap().line, 4);
+}
+
+const RECORD: &str = "\
+Name: CxxKit
+Tags:
+  - Name: Vector
+    Methods:
+      - Name: push_back
+        SwiftName: 'append(_:)'
+        Parameters:
+          - Position: 0
+            Nullability: N
+      - Name: at
+        SwiftName: 'at(_:)'
+        Parameters:
+          - Position: 1
+    Fields:
+      - Name: data
+        SwiftPrivate: true
+        Type: 'int *'
+      - Name: size
+        SwiftName: count
+        Nullability: N
+";
+
+#[test]
+fn methods_and_fields() {
+    let notes: ApiNotes = RECORD.parse().unwrap();
+    let tag = &notes.data.tags["Vector"];
+    let method = &tag.methods["push_back"];
+    assert_eq!(method.general.swift_name.as_deref().unwrap(), "append(_:)");
+    assert_eq!(method.parameters.len(), 1);
+    assert!(tag.fields["data"].general.swift_private);
+    assert_eq!(tag.fields["data"].type_.as_deref(), Some("int *"));
+    assert_eq!(tag.fields["size"].nullability, Some(Nullability::Nonnull));
+
+    let diagnostics = notes.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].path,
+        "Tags[Vector].Methods[at].Parameters[1]"
+    );
+    assert!(matches!(
+        diagnostics[0].kind,
+        DiagnosticKind::ParameterOutOfRange { position: 1, .. }
+    ));
+
+    let formatted = notes.to_canonical_string().unwrap();
+    assert!(formatted.contains("    Fields:\n      - Name: data\n"));
+    assert_eq!(formatted.parse::<ApiNotes>().unwrap(), notes);
+}
+
+#[test]
+fn duplicate_members() {
+    let err = "Name: A\nTags:\n  - Name: B\n    Methods:\n      - Name: f\n      - Name: f\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateEntry);
+    assert_eq!(err.mark().unwrap().line, 6);
+
+    let err = "Name: A\nTags:\n  - Name: B\n    Fields:\n      - Name: x\n      - Name: x\n"
+        .parse::<ApiNotes>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateEntry);
+    assert_eq!(err.mark().unwrap().line, 6);
+}
+
+#[test]
+fn enum_attributes() {
+    let source = "\
+Name: A
+Tags:
+  - Name: Flags
+    EnumExtensibility: open
+    FlagEnum: true
+  - Name: Mixed
+    EnumKind: NSOptions
+    FlagEnum: true
+  - Name: Legacy
+    EnumKind: NSClosedEnum
+";
+    let notes: ApiNotes = source.parse().unwrap();
+    let tag = &notes.data.tags["Flags"];
+    assert_eq!(tag.enum_extensibility, Some(EnumExtensibility::Open));
+    assert_eq!(tag.flag_enum, Some(true));
+
+    let diagnostics = notes.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "Tags[Mixed]");
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::EnumKindConflict);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "Tags[Mixed]: `EnumKind` can't be combined with `EnumExtensibility` or `FlagEnum`"
+    );
+
+    let formatted = notes.to_canonical_string().unwrap();
+    assert!(formatted.contains("    EnumExtensibility: open\n    FlagEnum: true\n"));
+    assert_eq!(formatted.parse::<ApiNotes>().unwrap(), notes);
+}
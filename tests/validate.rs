@@ -0,0 +1,158 @@
+use apinotes::{ApiNotes, DiagnosticKind};
+
+const SOURCE: &str = "\
+Name: SomeKit
+Classes:
+  - Name: A
+    Methods:
+      - Selector: 'initWithA:b:'
+        MethodKind: Instance
+        SwiftName: 'init(a:)'
+      - Selector: 'transform:'
+        MethodKind: Class
+        SwiftName: 'transform(_:)'
+        Parameters:
+          - Position: 1
+      - Selector: 'description'
+        MethodKind: Instance
+        SwiftName: 'description()'
+Functions:
+  - Name: f
+    SwiftName: 'A.f(self:)'
+    Parameters:
+      - Position: 0
+      - Position: 2
+  - Name: g
+    Parameters:
+      - Position: 5
+SwiftVersions:
+  - Version: 3.0
+    Protocols:
+      - Name: P
+        Methods:
+          - Selector: 'foo:'
+            MethodKind: Instance
+            SwiftName: 'foo()'
+";
+
+#[test]
+fn diagnostics() {
+    let notes: ApiNotes = SOURCE.parse().unwrap();
+    let diagnostics = notes.validate();
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.path.as_str(), diagnostic.kind.clone()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                "Classes[A].Methods[+transform:].Parameters[1]",
+                DiagnosticKind::ParameterOutOfRange {
+                    position: 1,
+                    num_args: 1
+                }
+            ),
+            (
+                "Classes[A].Methods[-initWithA:b:]",
+                DiagnosticKind::SwiftNameArity {
+                    expected: 2,
+                    found: 1
+                }
+            ),
+            (
+                "Functions[f].Parameters[2]",
+                DiagnosticKind::ParameterOutOfRange {
+                    position: 2,
+                    num_args: 1
+                }
+            ),
+            (
+                "SwiftVersions[3.0].Protocols[P].Methods[-foo:]",
+                DiagnosticKind::SwiftNameArity {
+                    expected: 1,
+                    found: 0
+                }
+            ),
+        ]
+    );
+    assert_eq!(
+        diagnostics[1].to_string(),
+        "Classes[A].Methods[-initWithA:b:]: `SwiftName` has 1 argument label(s), but the selector has 2 argument(s)"
+    );
+}
+
+#[test]
+fn valid() {
+    let notes: ApiNotes = "Name: A\nClasses:\n  - Name: B\n    Methods:\n      - Selector: 'a:b:'\n        MethodKind: Instance\n        SwiftName: 'a(_:b:)'\n        Parameters:\n          - Position: 1\n"
+        .parse()
+        .unwrap();
+    assert_eq!(notes.validate(), []);
+}
+
+#[test]
+fn swift_name_arity() {
+    let notes: ApiNotes = "\
+Name: A
+Classes:
+  - Name: B
+    Methods:
+      - Selector: 'removeItemAtURL:error:'
+        MethodKind: Instance
+        SwiftName: 'removeItem(at:)'
+      - Selector: 'getValue:forKey:'
+        MethodKind: Instance
+        SwiftName: 'value(forKey:)'
+        Parameters:
+          - Position: 0
+            Type: 'id _Nullable * _Nonnull'
+      - Selector: 'defaultB'
+        MethodKind: Class
+        SwiftName: 'init(default:)'
+      - Selector: 'setValue:forKey:'
+        MethodKind: Instance
+        SwiftName: 'set(forKey:)'
+        Parameters:
+          - Position: 0
+            Type: 'const char *'
+      - Selector: 'sharedB'
+        MethodKind: Class
+        SwiftName: 'init(_:)'
+      - Selector: 'copyB'
+        MethodKind: Instance
+        SwiftName: 'copy(with:)'
+"
+    .parse()
+    .unwrap();
+    let found: Vec<_> = notes
+        .validate()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.path, diagnostic.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                "Classes[B].Methods[+sharedB]".into(),
+                DiagnosticKind::SwiftNameArity {
+                    expected: 0,
+                    found: 1
+                }
+            ),
+            (
+                "Classes[B].Methods[-copyB]".into(),
+                DiagnosticKind::SwiftNameArity {
+                    expected: 0,
+                    found: 1
+                }
+            ),
+            (
+                "Classes[B].Methods[-setValue:forKey:]".into(),
+                DiagnosticKind::SwiftNameArity {
+                    expected: 2,
+                    found: 1
+                }
+            ),
+        ]
+    );
+}